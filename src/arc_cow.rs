@@ -1,23 +1,117 @@
 //! `ArcCow`.
 
 use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::RcCow;
+use crate::{MaybeStatic, RcCow, SharedCow, SharedPointer, Sliced};
 
-def_shared_cow! {
-    #[doc = "[`Cow`][`std::borrow::Cow`] with variant with shared [`Arc`][`std::sync::Arc`] data."]
-    pub def ArcCow<B>(std::sync::Arc<B>);
+/// [`Cow`][`std::borrow::Cow`] with variant with shared [`Arc`][`std::sync::Arc`] data.
+pub type ArcCow<'a, B> = SharedCow<'a, B, Arc<B>>;
+
+/// [`ArcCow`] which can be sliced without cloning the shared data.
+pub type SlicedArcCow<'a, B> = SharedCow<'a, B, Sliced<Arc<B>>>;
+
+/// [`ArcCow`] which can point to `'static` data without cloning it.
+pub type StaticArcCow<'a, B> = SharedCow<'a, B, MaybeStatic<Arc<B>>>;
+
+def_weak_cow! {
+    #[doc = "Weak pointer to the shared data of [`ArcCow`]."]
+    pub def WeakArcCow(sync::Arc) for ArcCow;
+}
+
+impl<'a, B> From<Arc<B>> for ArcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    fn from(s: Arc<B>) -> Self {
+        SharedCow::Shared(s)
+    }
+}
+
+/// The data is moved into the new shared data if the pointer is unique and
+/// [`SharedPointer::try_unwrap`] supports the data, and cloned otherwise, as
+/// [`RcCow::into_arccow`] does.
+impl<'a, B> From<Rc<B>> for ArcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+    Rc<B>: SharedPointer<B>,
+{
+    fn from(s: Rc<B>) -> Self {
+        RcCow::Shared(s).into_arccow()
+    }
+}
+
+/// `Shared(_)` data is shared without cloning.
+impl<'a, B> From<ArcCow<'a, B>> for Arc<B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+{
+    fn from(cow: ArcCow<'a, B>) -> Self {
+        cow.into_shared()
+    }
+}
+
+/// `Shared(_)` data is moved into the new shared data if the pointer is
+/// unique and [`SharedPointer::try_unwrap`] supports the data, and cloned
+/// otherwise.
+impl<'a, B> From<RcCow<'a, B>> for Arc<B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+    Rc<B>: SharedPointer<B>,
+{
+    fn from(cow: RcCow<'a, B>) -> Self {
+        cow.into_other_shared()
+    }
 }
-impl_cow! { ArcCow<B>(std::sync::Arc<B>); <A> }
 
-impl_str_like! { ArcCow, std::sync::Arc<str>, str, String }
-impl_str_like! { ArcCow, std::sync::Arc<std::path::Path>, std::path::Path, std::path::PathBuf }
-impl_str_like! { ArcCow, std::sync::Arc<std::ffi::OsStr>, std::ffi::OsStr, std::ffi::OsString }
+impl_c_str_conversions! { ArcCow, Arc }
+impl_str_conversions! { ArcCow, Arc }
+
+impl<'a> std::ops::Add<Arc<str>> for ArcCow<'a, str> {
+    type Output = ArcCow<'a, str>;
+
+    fn add(mut self, rhs: Arc<str>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<'a> std::ops::AddAssign<Arc<str>> for ArcCow<'a, str> {
+    fn add_assign(&mut self, rhs: Arc<str>) {
+        if !self.is_owned() && self.is_empty() {
+            *self = ArcCow::Shared(rhs)
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
+
+/// The data of `rhs` is copied, because it cannot be shared by [`ArcCow`].
+impl<'a> std::ops::Add<Rc<str>> for ArcCow<'a, str> {
+    type Output = ArcCow<'a, str>;
+
+    fn add(mut self, rhs: Rc<str>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+/// The data of `rhs` is copied, because it cannot be shared by [`ArcCow`].
+impl<'a> std::ops::AddAssign<Rc<str>> for ArcCow<'a, str> {
+    fn add_assign(&mut self, rhs: Rc<str>) {
+        if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
 
 impl<'a, B> ArcCow<'a, B>
 where
     B: 'a + ToOwned + ?Sized,
-    for<'b> &'b B: Into<Rc<B>>,
+    Rc<B>: SharedPointer<B>,
 {
     /// Creates an [`RcCow`] value.
     #[must_use]
@@ -26,10 +120,41 @@ where
         match *self {
             ArcCow::Borrowed(b) => RcCow::Borrowed(b),
             ArcCow::Owned(ref o) => RcCow::Owned(o.borrow().to_owned()),
-            ArcCow::Shared(ref s) => {
-                let b: &B = s.borrow();
-                RcCow::Shared(b.into())
-            },
+            ArcCow::Shared(ref s) => RcCow::Shared(SharedPointer::from_borrowed(&**s)),
+        }
+    }
+
+    /// Converts the value into an [`RcCow`] value.
+    ///
+    /// Unlike [`to_rccow`][`ArcCow::to_rccow`], `Owned(_)` data is moved
+    /// without cloning.
+    /// `Shared(_)` data is moved into a new [`Rc`][`std::rc::Rc`] without
+    /// cloning if the pointer is unique and [`SharedPointer::try_unwrap`]
+    /// supports the data (such as sized data), and cloned otherwise, because
+    /// [`Arc`][`std::sync::Arc`] allocation cannot be reused by [`Rc`][`std::rc::Rc`].
+    #[must_use]
+    pub fn into_rccow(self) -> RcCow<'a, B>
+    where
+        Arc<B>: SharedPointer<B>,
+    {
+        match self {
+            ArcCow::Borrowed(b) => RcCow::Borrowed(b),
+            ArcCow::Owned(o) => RcCow::Owned(o),
+            ArcCow::Shared(s) => RcCow::Shared(match SharedPointer::try_unwrap(s) {
+                Ok(o) => SharedPointer::from_owned(o),
+                Err(s) => SharedPointer::from_borrowed(&*s),
+            }),
         }
     }
 }
+
+impl<'a, B> From<RcCow<'a, B>> for ArcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+    Rc<B>: SharedPointer<B>,
+{
+    fn from(cow: RcCow<'a, B>) -> Self {
+        cow.into_arccow()
+    }
+}
@@ -0,0 +1,271 @@
+//! Traits for clone-on-write types.
+
+use std::borrow::Cow;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{ArcCow, RcCow, SharedCow, SharedPointer};
+
+/// Clone-on-write type.
+///
+/// This is implemented for [`Cow`][`std::borrow::Cow`], [`ArcCow`] and
+/// [`RcCow`], so that the functions can accept any of them.
+pub trait CowLike<'a, B>: Deref<Target = B> + Sized
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    /// Shared pointer returned by [`into_shared`][`CowLike::into_shared`].
+    ///
+    /// This is the pointer type of the `Shared(_)` variant for shared cows,
+    /// and [`Arc<B>`][`std::sync::Arc`] for [`Cow`][`std::borrow::Cow`].
+    type Shared: Deref<Target = B>;
+
+    /// Returns `true` if the value is `Borrowed(_)`.
+    fn is_borrowed(&self) -> bool;
+
+    /// Returns `true` if the value is `Owned(_)`.
+    fn is_owned(&self) -> bool;
+
+    /// Returns `true` if the value is `Shared(_)`.
+    ///
+    /// This is always `false` for [`Cow`][`std::borrow::Cow`].
+    fn is_shared(&self) -> bool;
+
+    /// Returns the reference to the data.
+    fn as_borrowed(&self) -> &B {
+        self
+    }
+
+    /// Creates a new owned value.
+    ///
+    /// This clones the value if necessary.
+    fn into_owned(self) -> <B as ToOwned>::Owned;
+
+    /// Returns mutable reference to the `Owned(_)` value.
+    ///
+    /// This clones the value if necessary.
+    fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned;
+
+    /// Converts the value into a [`Cow`][`std::borrow::Cow`] value.
+    ///
+    /// `Shared(_)` data is cloned into `Owned(_)` value.
+    fn into_cow(self) -> Cow<'a, B>;
+
+    /// Converts the value into a shared pointer.
+    ///
+    /// `Borrowed(_)` data is cloned, `Owned(_)` data is moved into the
+    /// pointer, and the pointer of `Shared(_)` value is returned as is.
+    /// Use [`into_arccow`][`CowLike::into_arccow`] or
+    /// [`into_rccow`][`CowLike::into_rccow`] to convert the value into the
+    /// other pointer type.
+    fn into_shared(self) -> Self::Shared
+    where
+        Self::Shared: SharedPointer<B>;
+
+    /// Converts the value into an [`ArcCow`] value.
+    ///
+    /// `Shared(_)` data is shared if it is already [`Arc`][`std::sync::Arc`],
+    /// and cloned otherwise.
+    fn into_arccow(self) -> ArcCow<'a, B>
+    where
+        Arc<B>: SharedPointer<B>;
+
+    /// Converts the value into an [`RcCow`] value.
+    ///
+    /// `Shared(_)` data is shared if it is already [`Rc`][`std::rc::Rc`], and
+    /// cloned otherwise.
+    fn into_rccow(self) -> RcCow<'a, B>
+    where
+        Rc<B>: SharedPointer<B>;
+}
+
+impl<'a, B> CowLike<'a, B> for Cow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    type Shared = Arc<B>;
+
+    fn is_borrowed(&self) -> bool {
+        match self {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }
+    }
+
+    fn is_owned(&self) -> bool {
+        !CowLike::is_borrowed(self)
+    }
+
+    fn is_shared(&self) -> bool {
+        false
+    }
+
+    fn into_owned(self) -> <B as ToOwned>::Owned {
+        Cow::into_owned(self)
+    }
+
+    fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned {
+        Cow::to_mut(self)
+    }
+
+    fn into_cow(self) -> Cow<'a, B> {
+        self
+    }
+
+    fn into_shared(self) -> Arc<B>
+    where
+        Arc<B>: SharedPointer<B>,
+    {
+        match self {
+            Cow::Borrowed(b) => SharedPointer::from_borrowed(b),
+            Cow::Owned(o) => SharedPointer::from_owned(o),
+        }
+    }
+
+    fn into_arccow(self) -> ArcCow<'a, B>
+    where
+        Arc<B>: SharedPointer<B>,
+    {
+        From::from(self)
+    }
+
+    fn into_rccow(self) -> RcCow<'a, B>
+    where
+        Rc<B>: SharedPointer<B>,
+    {
+        From::from(self)
+    }
+}
+
+impl<'a, B> CowLike<'a, B> for ArcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+{
+    type Shared = Arc<B>;
+
+    fn is_borrowed(&self) -> bool {
+        SharedCow::is_borrowed(self)
+    }
+
+    fn is_owned(&self) -> bool {
+        SharedCow::is_owned(self)
+    }
+
+    fn is_shared(&self) -> bool {
+        SharedCow::is_shared(self)
+    }
+
+    fn into_owned(self) -> <B as ToOwned>::Owned {
+        SharedCow::into_owned(self)
+    }
+
+    fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned {
+        SharedCow::to_mut(self)
+    }
+
+    fn into_cow(self) -> Cow<'a, B> {
+        self.into()
+    }
+
+    fn into_shared(self) -> Arc<B> {
+        SharedCow::into_shared(self)
+    }
+
+    fn into_arccow(self) -> ArcCow<'a, B>
+    where
+        Arc<B>: SharedPointer<B>,
+    {
+        self
+    }
+
+    fn into_rccow(self) -> RcCow<'a, B>
+    where
+        Rc<B>: SharedPointer<B>,
+    {
+        ArcCow::into_rccow(self)
+    }
+}
+
+impl<'a, B> CowLike<'a, B> for RcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Rc<B>: SharedPointer<B>,
+{
+    type Shared = Rc<B>;
+
+    fn is_borrowed(&self) -> bool {
+        SharedCow::is_borrowed(self)
+    }
+
+    fn is_owned(&self) -> bool {
+        SharedCow::is_owned(self)
+    }
+
+    fn is_shared(&self) -> bool {
+        SharedCow::is_shared(self)
+    }
+
+    fn into_owned(self) -> <B as ToOwned>::Owned {
+        SharedCow::into_owned(self)
+    }
+
+    fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned {
+        SharedCow::to_mut(self)
+    }
+
+    fn into_cow(self) -> Cow<'a, B> {
+        self.into()
+    }
+
+    fn into_shared(self) -> Rc<B> {
+        SharedCow::into_shared(self)
+    }
+
+    fn into_arccow(self) -> ArcCow<'a, B>
+    where
+        Arc<B>: SharedPointer<B>,
+    {
+        RcCow::into_arccow(self)
+    }
+
+    fn into_rccow(self) -> RcCow<'a, B>
+    where
+        Rc<B>: SharedPointer<B>,
+    {
+        self
+    }
+}
+
+/// Extension methods for [`Cow`][`std::borrow::Cow`].
+///
+/// Conversions into [`ArcCow`] and [`RcCow`] are provided by [`CowLike`].
+pub trait CowExt<'a, B>: CowLike<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    /// Converts the value into a shared cow, turning `Owned(_)` data into
+    /// `Shared(_)`.
+    ///
+    /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` data is moved
+    /// into the shared pointer.
+    fn share<P>(self) -> SharedCow<'a, B, P>
+    where
+        P: SharedPointer<B>;
+}
+
+impl<'a, B> CowExt<'a, B> for Cow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    fn share<P>(self) -> SharedCow<'a, B, P>
+    where
+        P: SharedPointer<B>,
+    {
+        match self {
+            Cow::Borrowed(b) => SharedCow::Borrowed(b),
+            Cow::Owned(o) => SharedCow::Shared(P::from_owned(o)),
+        }
+    }
+}
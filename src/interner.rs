@@ -0,0 +1,400 @@
+//! Interners.
+
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Deref;
+use std::rc::{self, Rc};
+use std::sync::{self, Arc, Mutex, PoisonError};
+
+use crate::{ArcCow, RcCow, SharedCow, SharedPointer};
+
+/// Default number of shards of [`ArcInterner`].
+const DEFAULT_SHARDS: usize = 16;
+
+/// Minimum number of entries of a weak shard to purge the dropped values.
+const MIN_PURGE_LEN: usize = 16;
+
+/// Shared pointer which has the weak counterpart.
+trait Downgrade: Sized {
+    /// Weak pointer.
+    type Weak;
+
+    /// Creates a new weak pointer.
+    fn downgrade(this: &Self) -> Self::Weak;
+
+    /// Attempts to upgrade the weak pointer.
+    fn upgrade(weak: &Self::Weak) -> Option<Self>;
+
+    /// Returns `true` if the data pointed by the weak pointer is alive.
+    fn is_alive(weak: &Self::Weak) -> bool;
+}
+
+impl<B: ?Sized> Downgrade for Rc<B> {
+    type Weak = rc::Weak<B>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        Rc::downgrade(this)
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        weak.upgrade()
+    }
+
+    fn is_alive(weak: &Self::Weak) -> bool {
+        weak.strong_count() != 0
+    }
+}
+
+impl<B: ?Sized> Downgrade for Arc<B> {
+    type Weak = sync::Weak<B>;
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        Arc::downgrade(this)
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        weak.upgrade()
+    }
+
+    fn is_alive(weak: &Self::Weak) -> bool {
+        weak.strong_count() != 0
+    }
+}
+
+/// Interned value.
+enum Entry<P: Downgrade> {
+    /// Value kept alive by the interner.
+    Strong(P),
+    /// Value which is freed when all users drop it.
+    Weak(<P as Downgrade>::Weak),
+}
+
+impl<P: Downgrade + Clone> Entry<P> {
+    /// Returns the shared pointer if the value is alive.
+    fn get(&self) -> Option<P> {
+        match self {
+            Entry::Strong(p) => Some(p.clone()),
+            Entry::Weak(w) => P::upgrade(w),
+        }
+    }
+
+    /// Returns `true` if the value is alive.
+    fn is_alive(&self) -> bool {
+        match self {
+            Entry::Strong(_) => true,
+            Entry::Weak(w) => P::is_alive(w),
+        }
+    }
+}
+
+/// Set of interned values.
+///
+/// Weak shards purge the dropped values on insertion when the number of
+/// entries has doubled since the last purge, so that the dead entries do not
+/// accumulate.
+struct Shard<P: Downgrade> {
+    /// Whether the values are held by weak pointers.
+    weak: bool,
+    /// Values grouped by the hash.
+    buckets: HashMap<u64, Vec<Entry<P>>>,
+    /// Number of entries.
+    len: usize,
+    /// Number of entries to purge the dropped values at.
+    purge_len: usize,
+}
+
+impl<B, P> Shard<P>
+where
+    B: ?Sized + Eq,
+    P: Downgrade + Clone + Deref<Target = B>,
+{
+    /// Creates a new empty shard.
+    fn new(weak: bool) -> Self {
+        Shard {
+            weak,
+            buckets: HashMap::new(),
+            len: 0,
+            purge_len: MIN_PURGE_LEN,
+        }
+    }
+
+    /// Returns the interned value equal to `value`.
+    fn get(&mut self, hash: u64, value: &B) -> Option<P> {
+        let bucket = self.buckets.get_mut(&hash)?;
+        let old_len = bucket.len();
+        bucket.retain(Entry::is_alive);
+        self.len -= old_len - bucket.len();
+        let found = bucket.iter().filter_map(Entry::get).find(|p| **p == *value);
+        if bucket.is_empty() {
+            self.buckets.remove(&hash);
+        }
+        found
+    }
+
+    /// Adds the value.
+    fn insert(&mut self, hash: u64, value: &P) {
+        let entry = if self.weak {
+            Entry::Weak(P::downgrade(value))
+        } else {
+            Entry::Strong(value.clone())
+        };
+        self.buckets.entry(hash).or_default().push(entry);
+        self.len += 1;
+        if self.weak && self.len >= self.purge_len {
+            self.purge();
+        }
+    }
+
+    /// Removes the dropped values.
+    fn purge(&mut self) {
+        self.buckets.retain(|_, bucket| {
+            bucket.retain(Entry::is_alive);
+            !bucket.is_empty()
+        });
+        self.len = self.buckets.values().map(Vec::len).sum();
+        self.purge_len = std::cmp::max(self.len * 2, MIN_PURGE_LEN);
+    }
+
+    /// Returns the number of entries, including the dropped values not purged yet.
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Thread-safe interner which returns [`ArcCow`] values sharing the data.
+///
+/// The values are distributed to the shards by their hash, and each shard is
+/// locked independently.
+pub struct ArcInterner<B>
+where
+    B: ?Sized,
+{
+    /// Hasher.
+    hasher: RandomState,
+    /// Shards.
+    shards: Box<[Mutex<Shard<Arc<B>>>]>,
+}
+
+impl<B> ArcInterner<B>
+where
+    B: ?Sized + Hash + Eq + ToOwned,
+    Arc<B>: SharedPointer<B>,
+{
+    /// Creates a new interner which keeps the values alive.
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new interner which frees the values when all users drop
+    /// them.
+    ///
+    /// The entries for the dropped values are removed when the number of
+    /// entries has doubled since the last removal, or by
+    /// [`purge`][`Self::purge`].
+    pub fn new_weak() -> Self {
+        Self::with_shards_weak(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new interner with the given number of shards, which keeps
+    /// the values alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is 0.
+    pub fn with_shards(count: usize) -> Self {
+        Self::with_shards_impl(count, false)
+    }
+
+    /// Creates a new interner with the given number of shards, which frees
+    /// the values when all users drop them.
+    ///
+    /// See [`new_weak`][`Self::new_weak`] for the removal of the entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is 0.
+    pub fn with_shards_weak(count: usize) -> Self {
+        Self::with_shards_impl(count, true)
+    }
+
+    /// Creates a new interner.
+    fn with_shards_impl(count: usize, weak: bool) -> Self {
+        assert!(count != 0, "shard count must be non-zero");
+        ArcInterner {
+            hasher: RandomState::new(),
+            shards: (0..count).map(|_| Mutex::new(Shard::new(weak))).collect(),
+        }
+    }
+
+    /// Returns the shard and the hash for the value.
+    fn shard(&self, value: &B) -> (&Mutex<Shard<Arc<B>>>, u64) {
+        let hash = self.hasher.hash_one(value);
+        let shard = &self.shards[(hash % self.shards.len() as u64) as usize];
+        (shard, hash)
+    }
+
+    /// Returns the `Shared(_)` value equal to `cow`.
+    ///
+    /// If the equal value is already interned, the interned data is shared
+    /// and `cow` is not cloned.
+    /// Otherwise, `cow` is turned into the shared data and interned.
+    pub fn intern<'a>(&self, cow: ArcCow<'_, B>) -> ArcCow<'a, B>
+    where
+        B: 'a,
+    {
+        let (shard, hash) = self.shard(&cow);
+        let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(shared) = shard.get(hash, &cow) {
+            return SharedCow::Shared(shared);
+        }
+        let shared = cow.into_shared();
+        shard.insert(hash, &shared);
+        SharedCow::Shared(shared)
+    }
+
+    /// Returns the interned `Shared(_)` value equal to `value` if available.
+    ///
+    /// This never clones the value.
+    pub fn get<'a>(&self, value: &B) -> Option<ArcCow<'a, B>>
+    where
+        B: 'a,
+    {
+        let (shard, hash) = self.shard(value);
+        let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+        shard.get(hash, value).map(SharedCow::Shared)
+    }
+
+    /// Removes the entries for the values already dropped.
+    pub fn purge(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap_or_else(PoisonError::into_inner).purge();
+        }
+    }
+
+    /// Returns the number of entries.
+    ///
+    /// This may include entries for the values already dropped.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap_or_else(PoisonError::into_inner).len())
+            .sum()
+    }
+
+    /// Returns `true` if the interner has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<B> Default for ArcInterner<B>
+where
+    B: ?Sized + Hash + Eq + ToOwned,
+    Arc<B>: SharedPointer<B>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Single-threaded interner which returns [`RcCow`] values sharing the data.
+pub struct RcInterner<B>
+where
+    B: ?Sized,
+{
+    /// Hasher.
+    hasher: RandomState,
+    /// Interned values.
+    shard: RefCell<Shard<Rc<B>>>,
+}
+
+impl<B> RcInterner<B>
+where
+    B: ?Sized + Hash + Eq + ToOwned,
+    Rc<B>: SharedPointer<B>,
+{
+    /// Creates a new interner which keeps the values alive.
+    pub fn new() -> Self {
+        Self::new_impl(false)
+    }
+
+    /// Creates a new interner which frees the values when all users drop
+    /// them.
+    ///
+    /// The entries for the dropped values are removed when the number of
+    /// entries has doubled since the last removal, or by
+    /// [`purge`][`Self::purge`].
+    pub fn new_weak() -> Self {
+        Self::new_impl(true)
+    }
+
+    /// Creates a new interner.
+    fn new_impl(weak: bool) -> Self {
+        RcInterner {
+            hasher: RandomState::new(),
+            shard: RefCell::new(Shard::new(weak)),
+        }
+    }
+
+    /// Returns the `Shared(_)` value equal to `cow`.
+    ///
+    /// If the equal value is already interned, the interned data is shared
+    /// and `cow` is not cloned.
+    /// Otherwise, `cow` is turned into the shared data and interned.
+    pub fn intern<'a>(&self, cow: RcCow<'_, B>) -> RcCow<'a, B>
+    where
+        B: 'a,
+    {
+        let hash = self.hasher.hash_one(&*cow);
+        let mut shard = self.shard.borrow_mut();
+        if let Some(shared) = shard.get(hash, &cow) {
+            return SharedCow::Shared(shared);
+        }
+        let shared = cow.into_shared();
+        shard.insert(hash, &shared);
+        SharedCow::Shared(shared)
+    }
+
+    /// Returns the interned `Shared(_)` value equal to `value` if available.
+    ///
+    /// This never clones the value.
+    pub fn get<'a>(&self, value: &B) -> Option<RcCow<'a, B>>
+    where
+        B: 'a,
+    {
+        let hash = self.hasher.hash_one(value);
+        self.shard
+            .borrow_mut()
+            .get(hash, value)
+            .map(SharedCow::Shared)
+    }
+
+    /// Removes the entries for the values already dropped.
+    pub fn purge(&self) {
+        self.shard.borrow_mut().purge();
+    }
+
+    /// Returns the number of entries.
+    ///
+    /// This may include entries for the values already dropped.
+    pub fn len(&self) -> usize {
+        self.shard.borrow().len()
+    }
+
+    /// Returns `true` if the interner has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<B> Default for RcInterner<B>
+where
+    B: ?Sized + Hash + Eq + ToOwned,
+    Rc<B>: SharedPointer<B>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
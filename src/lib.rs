@@ -1,14 +1,30 @@
 //! Shared clone-on-write type.
-#![feature(rust_2018_preview)]
-#![feature(macro_vis_matcher)]
 #![warn(missing_docs)]
 #![warn(rust_2018_idioms)]
 
-pub use crate::arc_cow::ArcCow;
-pub use crate::rc_cow::RcCow;
+pub use crate::arc_cow::{ArcCow, SlicedArcCow, StaticArcCow, WeakArcCow};
+pub use crate::cow_like::{CowExt, CowLike};
+pub use crate::interner::{ArcInterner, RcInterner};
+pub use crate::maybe_static::MaybeStatic;
+pub use crate::projected::Projected;
+pub use crate::rc_cow::{RcCow, SlicedRcCow, StaticRcCow, WeakRcCow};
+pub use crate::shared_cow::{CowKind, SharedCow};
+pub use crate::shared_pointer::SharedPointer;
+pub use crate::sliced::Sliced;
+pub use crate::try_to_owned::TryToOwned;
 
 #[macro_use]
 mod macros;
 
 mod arc_cow;
+mod cow_like;
+mod interner;
+mod maybe_static;
+mod projected;
 mod rc_cow;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod shared_cow;
+mod shared_pointer;
+mod sliced;
+mod try_to_owned;
@@ -1,4 +1,4 @@
-//! Macros to define and implement shared cow types.
+//! Macros to implement traits for shared cow types.
 
 // See <https://github.com/rust-lang/rust/blob/1.27.2/src/liballoc/vec.rs#L2097>.
 macro_rules! impl_eq_slice {
@@ -6,9 +6,10 @@ macro_rules! impl_eq_slice {
         impl_eq_slice! { $lhs, $rhs, Sized }
     };
     ($lhs:ty, $rhs:ty, $bound:ident) => {
-        impl<'a, 'b, A, B> PartialEq<$rhs> for $lhs
+        impl<'a, 'b, A, B, P> PartialEq<$rhs> for $lhs
         where
             A: $bound + PartialEq<B>,
+            P: std::ops::Deref<Target = [A]>,
         {
             #[inline]
             fn eq(&self, other: &$rhs) -> bool {
@@ -18,59 +19,118 @@ macro_rules! impl_eq_slice {
     };
 }
 
+macro_rules! impl_shared_pointer {
+    ($rc:ident, $borrowed:ty $(, $param:ident)*) => {
+        impl<$($param: Clone),*> SharedPointer<$borrowed> for $rc<$borrowed> {
+            fn from_borrowed(borrowed: &$borrowed) -> Self {
+                From::from(borrowed)
+            }
+
+            fn from_owned(owned: <$borrowed as ToOwned>::Owned) -> Self {
+                From::from(owned)
+            }
+
+            fn get_mut(this: &mut Self) -> Option<&mut $borrowed> {
+                $rc::get_mut(this)
+            }
+
+            fn strong_count(this: &Self) -> usize {
+                $rc::strong_count(this)
+            }
+
+            fn weak_count(this: &Self) -> usize {
+                $rc::weak_count(this)
+            }
+        }
+    };
+}
+
 macro_rules! impl_str_like {
-    ($cow:ident, $rc:ty, $borrowed:ty, $owned:ty) => {
-        impl<'a> From<&'a $borrowed> for $cow<'a, $borrowed> {
+    ($borrowed:ty, $owned:ty) => {
+        impl<'a, P> From<&'a $borrowed> for SharedCow<'a, $borrowed, P> {
             fn from(s: &'a $borrowed) -> Self {
-                $cow::Borrowed(s)
+                SharedCow::Borrowed(s)
             }
         }
 
-        impl<'a> From<$owned> for $cow<'a, $borrowed> {
+        impl<'a, P> From<$owned> for SharedCow<'a, $borrowed, P> {
             fn from(s: $owned) -> Self {
-                $cow::Owned(s)
+                SharedCow::Owned(s)
             }
         }
 
-        impl<'a> From<&'a $owned> for $cow<'a, $borrowed> {
+        impl<'a, P> From<&'a $owned> for SharedCow<'a, $borrowed, P> {
             fn from(s: &'a $owned) -> Self {
-                $cow::Owned(s.clone())
+                SharedCow::Borrowed(s)
+            }
+        }
+
+        impl<'a, P> From<&'a Box<$borrowed>> for SharedCow<'a, $borrowed, P> {
+            fn from(s: &'a Box<$borrowed>) -> Self {
+                SharedCow::Borrowed(s)
+            }
+        }
+
+        impl<'a, P> From<&'a std::rc::Rc<$borrowed>> for SharedCow<'a, $borrowed, P> {
+            fn from(s: &'a std::rc::Rc<$borrowed>) -> Self {
+                SharedCow::Borrowed(s)
+            }
+        }
+
+        impl<'a, P> From<&'a std::sync::Arc<$borrowed>> for SharedCow<'a, $borrowed, P> {
+            fn from(s: &'a std::sync::Arc<$borrowed>) -> Self {
+                SharedCow::Borrowed(s)
             }
         }
 
-        impl<'a> From<$rc> for $cow<'a, $borrowed> {
-            fn from(s: $rc) -> Self {
-                $cow::Shared(s)
+        impl<'a, P> From<SharedCow<'a, $borrowed, P>> for $owned
+        where
+            P: std::ops::Deref<Target = $borrowed>,
+        {
+            fn from(cow: SharedCow<'a, $borrowed, P>) -> Self {
+                cow.into_owned_cloned()
             }
         }
 
-        impl<'a> Into<$owned> for $cow<'a, $borrowed> {
-            fn into(self) -> $owned {
-                self.into_owned()
+        impl<'a, P> From<SharedCow<'a, $borrowed, P>> for Box<$borrowed>
+        where
+            P: std::ops::Deref<Target = $borrowed>,
+        {
+            fn from(cow: SharedCow<'a, $borrowed, P>) -> Self {
+                cow.into_owned_cloned().into()
             }
         }
 
-        impl_cmp! { $borrowed, $cow<'a, $borrowed>, $borrowed }
-        impl_cmp! { $borrowed, $cow<'a, $borrowed>, &'b $borrowed }
-        impl_cmp! { $borrowed, $cow<'a, $borrowed>, $owned }
-        impl_cmp! { $borrowed, $cow<'a, $borrowed>, &'b $owned }
-        impl_cmp! { $borrowed, $cow<'a, $borrowed>, std::borrow::Cow<'b, $borrowed> }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, $borrowed }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, &'b $borrowed }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, $owned }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, &'b $owned }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, std::borrow::Cow<'b, $borrowed> }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, Box<$borrowed> }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, std::rc::Rc<$borrowed> }
+        impl_cmp! { $borrowed, SharedCow<'a, $borrowed, P>, std::sync::Arc<$borrowed> }
     };
 }
 
 macro_rules! impl_eq {
-    ($lhs:ty, $rhs:ty) => {
-        impl<'a, 'b> PartialEq<$rhs> for $lhs {
+    ($base: ty, $lhs:ty, $rhs:ty) => {
+        impl<'a, 'b, P> PartialEq<$rhs> for $lhs
+        where
+            P: std::ops::Deref<Target = $base>,
+        {
             #[inline]
             fn eq(&self, other: &$rhs) -> bool {
-                self == other
+                <$base as PartialEq>::eq(self, other)
             }
         }
 
-        impl<'a, 'b> PartialEq<$lhs> for $rhs {
+        impl<'a, 'b, P> PartialEq<$lhs> for $rhs
+        where
+            P: std::ops::Deref<Target = $base>,
+        {
             #[inline]
             fn eq(&self, other: &$lhs) -> bool {
-                self == other
+                <$base as PartialEq>::eq(self, other)
             }
         }
     };
@@ -78,14 +138,20 @@ macro_rules! impl_eq {
 
 macro_rules! impl_partial_ord {
     ($base: ty, $lhs:ty, $rhs:ty) => {
-        impl<'a, 'b> PartialOrd<$rhs> for $lhs {
+        impl<'a, 'b, P> PartialOrd<$rhs> for $lhs
+        where
+            P: std::ops::Deref<Target = $base>,
+        {
             #[inline]
             fn partial_cmp(&self, other: &$rhs) -> Option<std::cmp::Ordering> {
                 <$base as PartialOrd>::partial_cmp(self, other)
             }
         }
 
-        impl<'a, 'b> PartialOrd<$lhs> for $rhs {
+        impl<'a, 'b, P> PartialOrd<$lhs> for $rhs
+        where
+            P: std::ops::Deref<Target = $base>,
+        {
             #[inline]
             fn partial_cmp(&self, other: &$lhs) -> Option<std::cmp::Ordering> {
                 <$base as PartialOrd>::partial_cmp(self, other)
@@ -96,479 +162,409 @@ macro_rules! impl_partial_ord {
 
 macro_rules! impl_cmp {
     ($base: ty, $lhs:ty, $rhs:ty) => {
-        impl_eq! { $lhs, $rhs }
+        impl_eq! { $base, $lhs, $rhs }
         impl_partial_ord! { $base, $lhs, $rhs }
     };
 }
 
-macro_rules! def_shared_cow {
-    ($(#[$meta:meta])* $vis:vis def $cow:ident<$typ:ident>($rc:ty);) => {
-        $(#[$meta])*
-        $vis enum $cow<'a, $typ>
+macro_rules! impl_cmp_os_str_like {
+    ($base:ty, $lhs:ty, $rhs:ty) => {
+        impl<'a, 'b, P> PartialEq<$rhs> for $lhs
         where
-            $typ: 'a + ToOwned + ?Sized,
+            P: std::ops::Deref<Target = $base>,
         {
-            /// Borrowed data.
-            Borrowed(&'a $typ),
-            /// Owned data.
-            Owned(<$typ as ToOwned>::Owned),
-            /// Shared data.
-            Shared($rc),
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                **self == *<$base>::new(other)
+            }
         }
-    };
-}
 
-macro_rules! impl_cow_basic {
-    ($cow:ident<$typ:ident>($rc:ty)) => {
-        impl<'a, $typ> $cow<'a, $typ>
+        impl<'a, 'b, P> PartialEq<$lhs> for $rhs
         where
-            $typ: 'a + ToOwned + ?Sized,
+            P: std::ops::Deref<Target = $base>,
         {
-            /// Creates a new owned value.
-            ///
-            /// This always clones the value.
-            #[must_use]
-            pub fn to_owned(&self) -> <$typ as ToOwned>::Owned {
-                use std::borrow::Borrow;
-                let b: &$typ = self.borrow();
-                b.to_owned()
-            }
-
-            /// Creates a new owned value.
-            ///
-            /// This behaves like [`Cow::into_owned`][`std::borrow::Cow::into_owned`].
-            /// This clones the value if necessary.
-            #[must_use]
-            pub fn into_owned(self) -> <$typ as ToOwned>::Owned {
-                match self {
-                    $cow::Borrowed(borrowed) => borrowed.to_owned(),
-                    $cow::Owned(owned) => owned,
-                    $cow::Shared(shared) => (*shared).to_owned(),
-                }
-            }
-
-            /// Returns mutable reference to the `Owned(_)` value.
-            ///
-            /// This behaves like [`Cow::to_mut`][`std::borrow::Cow::to_mut`].
-            /// This clones the value if necessary.
-            #[allow(unknown_lints, wrong_self_convention)]
-            pub fn to_mut(&mut self) -> &mut <$typ as ToOwned>::Owned {
-                *self = $cow::Owned(self.to_owned());
-                match *self {
-                    $cow::Owned(ref mut owned) => owned,
-                    _ => {
-                        unreachable!("Should never happen because `*self` must be `Owned` variant")
-                    },
-                }
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                *<$base>::new(self) == **other
             }
         }
-    };
-}
 
-macro_rules! impl_cow_to_shared {
-    ($cow:ident<$typ:ident>($rc:ty)) => {
-        impl<'a, $typ> $cow<'a, $typ>
+        impl<'a, 'b, P> PartialOrd<$rhs> for $lhs
         where
-            $typ: 'a + ToOwned + ?Sized,
-            for<'b> $rc: From<&'b $typ> + From<<$typ as ToOwned>::Owned>,
+            P: std::ops::Deref<Target = $base>,
         {
-            /// Creates a new shared value.
-            ///
-            /// This clones the value if necessary.
-            #[must_use]
-            pub fn into_shared(self) -> $rc {
-                match self {
-                    $cow::Borrowed(borrowed) => From::from(borrowed),
-                    $cow::Owned(owned) => From::from(owned),
-                    $cow::Shared(shared) => shared,
-                }
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<std::cmp::Ordering> {
+                (**self).partial_cmp(<$base>::new(other))
             }
         }
 
-        impl<'a, $typ> $cow<'a, $typ>
+        impl<'a, 'b, P> PartialOrd<$lhs> for $rhs
         where
-            $typ: 'a + ToOwned + ?Sized,
-            for<'b> &'b $typ: Into<$rc>,
+            P: std::ops::Deref<Target = $base>,
         {
-            /// Creates a new shared value.
-            ///
-            /// This always clones the value.
-            #[must_use]
-            pub fn to_shared(&self) -> $rc {
-                use std::borrow::Borrow;
-                match self {
-                    $cow::Borrowed(borrowed) => (*borrowed).into(),
-                    $cow::Owned(owned) => owned.borrow().into(),
-                    $cow::Shared(shared) => Clone::clone(shared),
-                }
-            }
-
-            /// Turns `self` into `Borrowed` or `Shared` variant.
-            ///
-            /// This may clone the value if necessary.
-            pub fn share(&mut self) -> &mut $cow<'a, $typ> {
-                use std::borrow::Borrow;
-                // It is perfect if I can do `*self = $cow::Shared(o.into())`
-                // for `if let $cow::Owned(o) = *self`, but it is unable in safe
-                // manner.
-                if let $cow::Owned(ref o) = *self {
-                    let b: &B = o.borrow();
-                    *self = $cow::Shared(b.into())
-                }
-                self
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<std::cmp::Ordering> {
+                <$base>::new(self).partial_cmp(&**other)
             }
         }
     };
 }
 
-macro_rules! impl_cow_std_traits {
-    ($cow:ident<$typ:ident>($rc:ty)) => {
-        impl<'a, T> From<&'a [T]> for $cow<'a, [T]>
+macro_rules! impl_str_add {
+    ($rhs:ty) => {
+        impl<'a, P> std::ops::Add<$rhs> for SharedCow<'a, str, P>
         where
-            T: Clone,
+            P: std::ops::Deref<Target = str>,
         {
-            fn from(v: &'a [T]) -> Self {
-                $cow::Borrowed(v)
+            type Output = SharedCow<'a, str, P>;
+
+            fn add(mut self, rhs: $rhs) -> Self::Output {
+                self += rhs;
+                self
             }
         }
+    };
+}
 
-        impl<'a, T> Into<Vec<T>> for $cow<'a, [T]>
+macro_rules! def_weak_cow {
+    ($(#[$meta:meta])* $vis:vis def $weak:ident($module:ident::$rc:ident) for $cow:ident;) => {
+        $(#[$meta])*
+        $vis struct $weak<B>(std::$module::Weak<B>)
         where
-            [T]: ToOwned<Owned = Vec<T>>,
-        {
-            fn into(self) -> Vec<T> {
-                self.into_owned()
-            }
-        }
+            B: ?Sized;
 
-        impl<'a, T> From<Vec<T>> for $cow<'a, [T]>
+        impl<B> $weak<B>
         where
-            T: Clone,
+            B: ?Sized,
         {
-            fn from(v: Vec<T>) -> Self {
-                $cow::Owned(v)
+            /// Attempts to upgrade the weak pointer to a `Shared(_)` value.
+            ///
+            /// Returns `None` if the data has already been dropped.
+            #[must_use]
+            pub fn upgrade(&self) -> Option<$cow<'static, B>>
+            where
+                B: 'static + ToOwned,
+            {
+                self.0.upgrade().map(SharedCow::Shared)
             }
-        }
 
-        impl<'a, T> From<&'a Vec<T>> for $cow<'a, [T]>
-        where
-            T: Clone,
-        {
-            fn from(v: &'a Vec<T>) -> Self {
-                $cow::Owned(v.clone())
+            /// Returns the number of strong pointers to the data.
+            pub fn strong_count(&self) -> usize {
+                self.0.strong_count()
             }
-        }
 
-        impl<'a, B> AsRef<B> for $cow<'a, B>
-        where
-            B: 'a + ToOwned + ?Sized,
-        {
-            fn as_ref(&self) -> &B {
-                self
+            /// Returns `true` if the two weak pointers point to the same data.
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                std::$module::Weak::ptr_eq(&self.0, &other.0)
             }
         }
 
-        impl<'a, B> std::borrow::Borrow<B> for $cow<'a, B>
+        impl<'a, B> $cow<'a, B>
         where
             B: 'a + ToOwned + ?Sized,
         {
-            fn borrow(&self) -> &B {
-                &**self
+            /// Creates a weak pointer to the shared data if the value is
+            /// `Shared(_)`.
+            pub fn downgrade(&self) -> Option<$weak<B>> {
+                self.as_shared().map(|s| $weak(std::$module::$rc::downgrade(s)))
             }
         }
 
-        impl<'a, B> Clone for $cow<'a, B>
+        impl<B> Clone for $weak<B>
         where
-            B: 'a + ToOwned + ?Sized,
+            B: ?Sized,
         {
             fn clone(&self) -> Self {
-                use std::borrow::Borrow;
-                match self {
-                    $cow::Borrowed(b) => $cow::Borrowed(b),
-                    $cow::Owned(o) => $cow::Owned(o.borrow().to_owned()),
-                    $cow::Shared(s) => $cow::Shared(Clone::clone(s)),
-                }
+                $weak(self.0.clone())
             }
         }
 
-        impl<'a> From<$cow<'a, str>> for Box<dyn std::error::Error> {
-            fn from(err: $cow<'a, str>) -> Self {
-                let err: String = err.into();
-                From::from(err)
-            }
-        }
-
-        impl<'a, 'b> From<$cow<'b, str>> for Box<dyn std::error::Error + Send + Sync + 'a> {
-            fn from(err: $cow<'b, str>) -> Self {
-                let err: String = err.into();
-                From::from(err)
-            }
-        }
-
-        impl<'a, B> Into<std::borrow::Cow<'a, B>> for $cow<'a, B>
+        impl<B> std::fmt::Debug for $weak<B>
         where
-            B: ?Sized + ToOwned,
+            B: ?Sized,
         {
-            fn into(self) -> std::borrow::Cow<'a, B> {
-                use std::borrow::Borrow;
-                match self {
-                    $cow::Borrowed(b) => std::borrow::Cow::Borrowed(b),
-                    $cow::Owned(b) => std::borrow::Cow::Owned(b),
-                    $cow::Shared(s) => {
-                        let b: &B = s.borrow();
-                        std::borrow::Cow::Owned(b.to_owned())
-                    },
-                }
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("(Weak)")
             }
         }
 
-        impl<'a, B> From<std::borrow::Cow<'a, B>> for $cow<'a, B>
+        /// Compares the identity of the data, not the content.
+        impl<B> PartialEq for $weak<B>
         where
-            B: ?Sized + ToOwned,
+            B: ?Sized,
         {
-            fn from(cow: std::borrow::Cow<'a, B>) -> Self {
-                match cow {
-                    std::borrow::Cow::Borrowed(b) => $cow::Borrowed(b),
-                    std::borrow::Cow::Owned(o) => $cow::Owned(o),
-                }
+            fn eq(&self, other: &Self) -> bool {
+                self.ptr_eq(other)
             }
         }
 
-        impl<'a, B> std::hash::Hash for $cow<'a, B>
+        impl<B> Eq for $weak<B> where B: ?Sized {}
+
+        /// Hashes the identity of the data, not the content.
+        impl<B> std::hash::Hash for $weak<B>
         where
-            B: ?Sized + std::hash::Hash + ToOwned,
+            B: ?Sized,
         {
-            #[inline]
             fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-                std::hash::Hash::hash(&**self, state)
+                (self.0.as_ptr() as *const ()).hash(state)
             }
         }
+    };
+}
 
-        impl<'a> std::iter::FromIterator<char> for $cow<'a, str> {
-            fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
-                $cow::Owned(std::iter::FromIterator::from_iter(iter))
-            }
+// Copies the shared data into the new shared string if it is valid Unicode,
+// and returns the original `Shared(_)` value otherwise.
+macro_rules! shared_to_str {
+    ($rc:ident, $shared:ident.$($to_str:tt)*) => {
+        match $shared.$($to_str)* {
+            Some(v) => Ok(SharedCow::Shared($rc::from(v))),
+            None => Err(SharedCow::Shared($shared)),
         }
+    };
+}
 
-        impl<'a, 'b> std::iter::FromIterator<&'b str> for $cow<'a, str> {
-            fn from_iter<I: IntoIterator<Item = &'b str>>(iter: I) -> Self {
-                $cow::Owned(std::iter::FromIterator::from_iter(iter))
+macro_rules! impl_c_str_conversions {
+    ($cow:ident, $rc:ident) => {
+        impl<'a> $cow<'a, std::ffi::CStr> {
+            /// Converts the value into the bytes without the trailing nul byte.
+            ///
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data, because
+            /// the allocation cannot be reused without the trailing nul byte.
+            #[must_use]
+            pub fn into_bytes(self) -> $cow<'a, [u8]> {
+                match self {
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(b.to_bytes()),
+                    SharedCow::Owned(o) => SharedCow::Owned(o.into_bytes()),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(s.to_bytes())),
+                }
             }
-        }
 
-        impl<'a> std::iter::FromIterator<String> for $cow<'a, str> {
-            fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
-                $cow::Owned(std::iter::FromIterator::from_iter(iter))
+            /// Converts the value into the string if it is valid UTF-8.
+            ///
+            /// Returns the original value on error.
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data, because
+            /// the allocation cannot be reused without the trailing nul byte.
+            pub fn into_string(self) -> Result<$cow<'a, str>, Self> {
+                match self {
+                    SharedCow::Borrowed(b) => match b.to_str() {
+                        Ok(s) => Ok(SharedCow::Borrowed(s)),
+                        Err(_) => Err(SharedCow::Borrowed(b)),
+                    },
+                    SharedCow::Owned(o) => o
+                        .into_string()
+                        .map(SharedCow::Owned)
+                        .map_err(|e| SharedCow::Owned(e.into_cstring())),
+                    SharedCow::Shared(s) => shared_to_str!($rc, s.to_str().ok()),
+                }
             }
         }
 
-        impl<'a> std::iter::FromIterator<$cow<'a, str>> for String {
-            fn from_iter<I: IntoIterator<Item = $cow<'a, str>>>(iter: I) -> Self {
-                let mut buf = String::new();
-                buf.extend(iter);
-                buf
+        impl_c_str_conversions! { @from $cow, $rc, str }
+        impl_c_str_conversions! { @from $cow, $rc, [u8] }
+    };
+    (@from $cow:ident, $rc:ident, $borrowed:ty) => {
+        impl<'a> $cow<'a, $borrowed> {
+            /// Converts the value into the C string if it has no nul bytes
+            /// except for the optional trailing one.
+            ///
+            /// Returns the original value on error.
+            /// `Borrowed(_)` value is kept borrowed if it ends with a nul
+            /// byte, and otherwise cloned into `Owned(_)` value.
+            /// `Owned(_)` buffer is moved, and `Shared(_)` data is cloned into
+            /// the new shared data, because the trailing nul byte needs to be
+            /// appended.
+            pub fn into_c_str(self) -> Result<$cow<'a, std::ffi::CStr>, Self> {
+                use std::ffi::{CStr, CString};
+
+                let bytes: &[u8] = (*self).as_ref();
+                if let SharedCow::Borrowed(b) = self {
+                    let bytes: &'a [u8] = b.as_ref();
+                    if let Ok(c) = CStr::from_bytes_with_nul(bytes) {
+                        return Ok(SharedCow::Borrowed(c));
+                    }
+                }
+                let len = match bytes.split_last() {
+                    Some((&0, init)) => init.len(),
+                    _ => bytes.len(),
+                };
+                if bytes[..len].contains(&0) {
+                    return Err(self);
+                }
+                let to_c_string = |bytes: Vec<u8>| {
+                    CString::new(bytes).expect("should never fail: no interior nul bytes")
+                };
+                Ok(match self {
+                    SharedCow::Borrowed(b) => {
+                        let bytes: &[u8] = b.as_ref();
+                        SharedCow::Owned(to_c_string(bytes[..len].to_vec()))
+                    },
+                    SharedCow::Owned(o) => {
+                        let mut bytes: Vec<u8> = o.into();
+                        bytes.truncate(len);
+                        SharedCow::Owned(to_c_string(bytes))
+                    },
+                    SharedCow::Shared(s) => {
+                        let bytes: &[u8] = (*s).as_ref();
+                        SharedCow::Shared($rc::from(to_c_string(bytes[..len].to_vec())))
+                    },
+                })
             }
         }
+    };
+}
 
-        impl<'a, T> std::iter::FromIterator<T> for $cow<'a, [T]>
-        where
-            T: Clone,
-        {
-            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-                $cow::Owned(std::iter::FromIterator::from_iter(iter))
+macro_rules! impl_str_conversions {
+    ($cow:ident, $rc:ident) => {
+        impl<'a> $cow<'a, str> {
+            /// Converts the string into the bytes.
+            ///
+            /// This never clones the data: `Borrowed(_)` value is kept
+            /// borrowed, `Owned(_)` buffer is moved, and `Shared(_)` data is
+            /// shared.
+            #[must_use]
+            pub fn into_bytes(self) -> $cow<'a, [u8]> {
+                match self {
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(b.as_bytes()),
+                    SharedCow::Owned(o) => SharedCow::Owned(o.into_bytes()),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(s)),
+                }
             }
-        }
 
-        impl<'a> Extend<$cow<'a, str>> for String {
-            fn extend<I: IntoIterator<Item = $cow<'a, str>>>(&mut self, iter: I) {
-                for s in iter {
-                    self.push_str(&s);
+            /// Converts the bytes into the string if they are valid UTF-8.
+            ///
+            /// Returns the original value on error.
+            /// This never clones the data: `Borrowed(_)` value is kept
+            /// borrowed, `Owned(_)` buffer is moved, and `Shared(_)` data is
+            /// shared.
+            pub fn from_utf8(bytes: $cow<'a, [u8]>) -> Result<Self, $cow<'a, [u8]>> {
+                match bytes {
+                    SharedCow::Borrowed(b) => match std::str::from_utf8(b) {
+                        Ok(s) => Ok(SharedCow::Borrowed(s)),
+                        Err(_) => Err(SharedCow::Borrowed(b)),
+                    },
+                    SharedCow::Owned(o) => String::from_utf8(o)
+                        .map(SharedCow::Owned)
+                        .map_err(|e| SharedCow::Owned(e.into_bytes())),
+                    SharedCow::Shared(s) => {
+                        if std::str::from_utf8(&s).is_err() {
+                            return Err(SharedCow::Shared(s));
+                        }
+                        let raw = $rc::into_raw(s) as *const str;
+                        // This is safe because `str` has the same layout as
+                        // `[u8]`, and the bytes are valid UTF-8.
+                        Ok(SharedCow::Shared(unsafe { $rc::from_raw(raw) }))
+                    },
                 }
             }
-        }
 
-        impl<'a, B> std::fmt::Debug for $cow<'a, B>
-        where
-            B: std::fmt::Debug + ToOwned + ?Sized,
-            <B as ToOwned>::Owned: std::fmt::Debug,
-        {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            /// Converts the string into the OS string.
+            ///
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            #[must_use]
+            pub fn into_os_str(self) -> $cow<'a, std::ffi::OsStr> {
+                use std::ffi::{OsStr, OsString};
                 match self {
-                    $cow::Borrowed(b) => std::fmt::Debug::fmt(b, f),
-                    $cow::Owned(o) => std::fmt::Debug::fmt(o, f),
-                    $cow::Shared(s) => std::fmt::Debug::fmt(s, f),
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(OsStr::new(b)),
+                    SharedCow::Owned(o) => SharedCow::Owned(OsString::from(o)),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(OsStr::new(&*s))),
                 }
             }
-        }
 
-        impl<'a, B> std::fmt::Display for $cow<'a, B>
-        where
-            B: std::fmt::Display + ToOwned + ?Sized,
-            <B as ToOwned>::Owned: std::fmt::Display,
-        {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            /// Converts the string into the path.
+            ///
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            #[must_use]
+            pub fn into_path(self) -> $cow<'a, std::path::Path> {
+                use std::path::{Path, PathBuf};
                 match self {
-                    $cow::Borrowed(b) => std::fmt::Display::fmt(b, f),
-                    $cow::Owned(o) => std::fmt::Display::fmt(o, f),
-                    $cow::Shared(s) => std::fmt::Display::fmt(s, f),
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(Path::new(b)),
+                    SharedCow::Owned(o) => SharedCow::Owned(PathBuf::from(o)),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(Path::new(&*s))),
                 }
             }
         }
 
-        impl<'a, B> std::ops::Deref for $cow<'a, B>
-        where
-            B: 'a + ToOwned + ?Sized,
-        {
-            type Target = B;
-
-            fn deref(&self) -> &B {
-                use std::borrow::Borrow;
+        impl<'a> $cow<'a, std::ffi::OsStr> {
+            /// Converts the OS string into the string if it is valid Unicode.
+            ///
+            /// Returns the original value on error.
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            pub fn into_string(self) -> Result<$cow<'a, str>, Self> {
                 match self {
-                    $cow::Borrowed(borrowed) => *borrowed,
-                    $cow::Owned(owned) => owned.borrow(),
-                    $cow::Shared(shared) => (**shared).borrow(),
+                    SharedCow::Borrowed(b) => match b.to_str() {
+                        Some(s) => Ok(SharedCow::Borrowed(s)),
+                        None => Err(SharedCow::Borrowed(b)),
+                    },
+                    SharedCow::Owned(o) => o
+                        .into_string()
+                        .map(SharedCow::Owned)
+                        .map_err(SharedCow::Owned),
+                    SharedCow::Shared(s) => shared_to_str!($rc, s.to_str()),
                 }
             }
-        }
-
-        impl<'a, B> Default for $cow<'a, B>
-        where
-            B: 'a + ToOwned + ?Sized,
-            <B as ToOwned>::Owned: Default,
-        {
-            fn default() -> Self {
-                $cow::Owned(<B as ToOwned>::Owned::default())
-            }
-        }
-
-        impl<'a> std::ops::Add<&'a str> for $cow<'a, str> {
-            type Output = $cow<'a, str>;
-
-            fn add(mut self, rhs: &'a str) -> Self::Output {
-                self += rhs;
-                self
-            }
-        }
-
-        impl<'a> std::ops::Add<$cow<'a, str>> for $cow<'a, str> {
-            type Output = $cow<'a, str>;
-
-            fn add(mut self, rhs: $cow<'a, str>) -> Self::Output {
-                self += rhs;
-                self
-            }
-        }
 
-        impl<'a> std::ops::AddAssign<&'a str> for $cow<'a, str> {
-            fn add_assign(&mut self, rhs: &'a str) {
-                if self.is_empty() {
-                    *self = $cow::Borrowed(rhs)
-                } else if rhs.is_empty() {
-                    return;
-                } else {
-                    match *self {
-                        $cow::Borrowed(lhs) => {
-                            let mut s = String::with_capacity(rhs.len() + rhs.len());
-                            s.push_str(lhs);
-                            *self = $cow::Owned(s)
-                        },
-                        $cow::Shared(ref lhs) => {
-                            let mut s = String::with_capacity(rhs.len() + rhs.len());
-                            s.push_str(lhs);
-                            *self = $cow::Owned(s)
-                        },
-                        _ => {},
-                    }
-                    self.to_mut().push_str(rhs);
+            /// Converts the OS string into the path.
+            ///
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            #[must_use]
+            pub fn into_path(self) -> $cow<'a, std::path::Path> {
+                use std::path::{Path, PathBuf};
+                match self {
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(Path::new(b)),
+                    SharedCow::Owned(o) => SharedCow::Owned(PathBuf::from(o)),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(Path::new(&*s))),
                 }
             }
         }
 
-        impl<'a> std::ops::AddAssign<$cow<'a, str>> for $cow<'a, str> {
-            fn add_assign(&mut self, rhs: $cow<'a, str>) {
-                if self.is_empty() {
-                    *self = rhs;
-                } else if rhs.is_empty() {
-                    return;
-                } else {
-                    match *self {
-                        $cow::Borrowed(lhs) => {
-                            let mut s = String::with_capacity(rhs.len() + rhs.len());
-                            s.push_str(lhs);
-                            *self = $cow::Owned(s)
-                        },
-                        $cow::Shared(ref lhs) => {
-                            let mut s = String::with_capacity(rhs.len() + rhs.len());
-                            s.push_str(lhs);
-                            *self = $cow::Owned(s)
-                        },
-                        _ => {},
-                    }
-                    self.to_mut().push_str(&rhs);
+        impl<'a> $cow<'a, std::path::Path> {
+            /// Converts the path into the string if it is valid Unicode.
+            ///
+            /// Returns the original value on error.
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            pub fn into_string(self) -> Result<$cow<'a, str>, Self> {
+                match self {
+                    SharedCow::Borrowed(b) => match b.to_str() {
+                        Some(s) => Ok(SharedCow::Borrowed(s)),
+                        None => Err(SharedCow::Borrowed(b)),
+                    },
+                    SharedCow::Owned(o) => o
+                        .into_os_string()
+                        .into_string()
+                        .map(SharedCow::Owned)
+                        .map_err(|o| SharedCow::Owned(o.into())),
+                    SharedCow::Shared(s) => shared_to_str!($rc, s.to_str()),
                 }
             }
-        }
-    };
-}
-
-macro_rules! impl_cow_cmp_traits {
-    ($cow:ident<$typ:ident>($rc:ty); <$other_typ:ident>) => {
-        impl<'a, 'b, $other_typ, $typ> PartialEq<$cow<'b, $typ>> for $cow<'a, $other_typ>
-        where
-            $other_typ: ?Sized + PartialEq<$typ> + ToOwned,
-            $typ: ?Sized + ToOwned,
-        {
-            #[inline]
-            fn eq(&self, other: &$cow<'b, $typ>) -> bool {
-                **self == **other
-            }
-        }
-
-        impl_eq_slice! { $cow<'a, [$other_typ]>, &'b [$typ], Clone }
-        impl_eq_slice! { $cow<'a, [$other_typ]>, &'b mut [$typ], Clone }
-        impl_eq_slice! { $cow<'a, [$other_typ]>, Vec<$typ>, Clone }
-        impl_eq_slice! { $cow<'a, [$other_typ]>, &'b Vec<$typ>, Clone }
-
-        impl<'a, 'b, $other_typ, $typ> PartialEq<std::borrow::Cow<'b, [$typ]>> for $cow<'a, [$other_typ]>
-        where
-            $other_typ: Clone + ToOwned + PartialEq<$typ>,
-            $typ: Clone + ToOwned,
-        {
-            #[inline]
-            fn eq(&self, other: &std::borrow::Cow<'b, [$typ]>) -> bool {
-                self[..] == other[..]
-            }
-        }
-
-        impl<'a, $typ> Eq for $cow<'a, $typ> where $typ: ?Sized + Eq + ToOwned {}
-
-        impl<'a, $typ> PartialOrd for $cow<'a, $typ>
-        where
-            $typ: ?Sized + PartialOrd + ToOwned,
-        {
-            #[inline]
-            fn partial_cmp(&self, other: &$cow<'a, $typ>) -> Option<std::cmp::Ordering> {
-                PartialOrd::partial_cmp(&**self, &**other)
-            }
-        }
 
-        impl<'a, $typ> Ord for $cow<'a, $typ>
-        where
-            $typ: ?Sized + Ord + ToOwned,
-        {
-            #[inline]
-            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-                Ord::cmp(&**self, &**other)
+            /// Converts the path into the OS string.
+            ///
+            /// `Borrowed(_)` value is kept borrowed, and `Owned(_)` buffer is
+            /// moved.
+            /// `Shared(_)` data is cloned into the new shared data.
+            #[must_use]
+            pub fn into_os_str(self) -> $cow<'a, std::ffi::OsStr> {
+                match self {
+                    SharedCow::Borrowed(b) => SharedCow::Borrowed(b.as_os_str()),
+                    SharedCow::Owned(o) => SharedCow::Owned(o.into_os_string()),
+                    SharedCow::Shared(s) => SharedCow::Shared($rc::from(s.as_os_str())),
+                }
             }
         }
     };
 }
-
-macro_rules! impl_cow {
-    ($cow:ident<$typ:ident>($rc:ty); <$other_typ:ident>) => {
-        impl_cow_basic! { $cow<$typ>($rc) }
-        impl_cow_to_shared! { $cow<$typ>($rc) }
-        impl_cow_std_traits! { $cow<$typ>($rc) }
-        impl_cow_cmp_traits! { $cow<$typ>($rc); <$other_typ> }
-    };
-}
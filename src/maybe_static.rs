@@ -0,0 +1,139 @@
+//! `MaybeStatic`.
+
+use std::ops::Deref;
+
+use crate::{SharedCow, SharedPointer};
+
+/// Shared pointer which may point to `'static` data instead of the data
+/// shared by `P`.
+///
+/// `'static` data is neither cloned nor reference counted, so it is kept as
+/// is by the conversions detaching the value from the borrow lifetime, such as
+/// [`into_shared`][`SharedCow::into_shared`] and
+/// [`into_static`][`SharedCow::into_static`].
+pub enum MaybeStatic<P>
+where
+    P: Deref,
+    <P as Deref>::Target: 'static,
+{
+    /// `'static` data.
+    Static(&'static <P as Deref>::Target),
+    /// Shared data.
+    Shared(P),
+}
+
+impl<P> Clone for MaybeStatic<P>
+where
+    P: Clone + Deref,
+    <P as Deref>::Target: 'static,
+{
+    fn clone(&self) -> Self {
+        match self {
+            MaybeStatic::Static(s) => MaybeStatic::Static(*s),
+            MaybeStatic::Shared(s) => MaybeStatic::Shared(s.clone()),
+        }
+    }
+}
+
+impl<P> Deref for MaybeStatic<P>
+where
+    P: Deref,
+    <P as Deref>::Target: 'static,
+{
+    type Target = <P as Deref>::Target;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MaybeStatic::Static(s) => s,
+            MaybeStatic::Shared(s) => s,
+        }
+    }
+}
+
+/// `'static` data is not reference counted, so the counts are 0 for it, and
+/// [`SharedCow::strong_count`] and [`SharedCow::weak_count`] return `None`.
+impl<B, P> SharedPointer<B> for MaybeStatic<P>
+where
+    B: 'static + ToOwned + ?Sized,
+    P: SharedPointer<B>,
+{
+    fn from_borrowed(borrowed: &B) -> Self {
+        MaybeStatic::Shared(P::from_borrowed(borrowed))
+    }
+
+    fn from_owned(owned: <B as ToOwned>::Owned) -> Self {
+        MaybeStatic::Shared(P::from_owned(owned))
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut B> {
+        match this {
+            MaybeStatic::Static(_) => None,
+            MaybeStatic::Shared(s) => P::get_mut(s),
+        }
+    }
+
+    fn try_unwrap(this: Self) -> Result<<B as ToOwned>::Owned, Self> {
+        match this {
+            MaybeStatic::Static(s) => Err(MaybeStatic::Static(s)),
+            MaybeStatic::Shared(s) => P::try_unwrap(s).map_err(MaybeStatic::Shared),
+        }
+    }
+
+    fn strong_count(this: &Self) -> usize {
+        match this {
+            MaybeStatic::Static(_) => 0,
+            MaybeStatic::Shared(s) => P::strong_count(s),
+        }
+    }
+
+    fn weak_count(this: &Self) -> usize {
+        match this {
+            MaybeStatic::Static(_) => 0,
+            MaybeStatic::Shared(s) => P::weak_count(s),
+        }
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, MaybeStatic<P>>
+where
+    B: 'static + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    /// Creates a new `Shared(_)` value pointing to the `'static` data.
+    ///
+    /// This never clones the value.
+    pub const fn from_static(borrowed: &'static B) -> Self {
+        SharedCow::Shared(MaybeStatic::Static(borrowed))
+    }
+
+    /// Returns `true` if the value points to the `'static` data.
+    pub fn is_static(&self) -> bool {
+        matches!(self, SharedCow::Shared(MaybeStatic::Static(_)))
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'static + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    /// Converts the value into a value which can point to the `'static` data.
+    #[must_use]
+    pub fn into_maybe_static(self) -> SharedCow<'a, B, MaybeStatic<P>> {
+        match self {
+            SharedCow::Borrowed(b) => SharedCow::Borrowed(b),
+            SharedCow::Owned(o) => SharedCow::Owned(o),
+            SharedCow::Shared(s) => SharedCow::Shared(MaybeStatic::Shared(s)),
+        }
+    }
+}
+
+impl<'a, B, P> From<SharedCow<'a, B, P>> for SharedCow<'a, B, MaybeStatic<P>>
+where
+    B: 'static + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    fn from(cow: SharedCow<'a, B, P>) -> Self {
+        cow.into_maybe_static()
+    }
+}
@@ -0,0 +1,84 @@
+//! `Projected`.
+
+use std::ops::Deref;
+
+use crate::SharedCow;
+
+/// Shared pointer to a part of the data shared by `P`.
+///
+/// This keeps the whole shared data alive and points to the part of it, so
+/// projecting the `Shared(_)` variant never clones the data.
+pub struct Projected<P, F>
+where
+    P: Deref,
+    F: ?Sized,
+{
+    /// Shared pointer to the whole data.
+    parent: P,
+    /// Function to get the part from the whole data.
+    project: fn(&<P as Deref>::Target) -> &F,
+}
+
+impl<P, F> Projected<P, F>
+where
+    P: Deref,
+    F: ?Sized,
+{
+    /// Creates a new pointer to the part of the data.
+    pub fn new(parent: P, project: fn(&<P as Deref>::Target) -> &F) -> Self {
+        Projected { parent, project }
+    }
+
+    /// Returns the reference to the shared pointer to the whole data.
+    pub fn parent(&self) -> &P {
+        &self.parent
+    }
+}
+
+impl<P, F> Clone for Projected<P, F>
+where
+    P: Clone + Deref,
+    F: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Projected {
+            parent: self.parent.clone(),
+            project: self.project,
+        }
+    }
+}
+
+impl<P, F> Deref for Projected<P, F>
+where
+    P: Deref,
+    F: ?Sized,
+{
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        (self.project)(&self.parent)
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Clone + Deref<Target = B>,
+{
+    /// Returns a value pointing to the part of the data.
+    ///
+    /// `Borrowed(_)` value borrows the part, and `Shared(_)` value keeps the
+    /// whole shared data alive and points to the part without cloning.
+    /// `Owned(_)` value clones the part into a new `Owned(_)` value.
+    pub fn project<F>(&self, project: fn(&B) -> &F) -> SharedCow<'a, F, Projected<P, F>>
+    where
+        F: 'a + ToOwned + ?Sized,
+    {
+        use std::borrow::Borrow;
+        match self {
+            SharedCow::Borrowed(b) => SharedCow::Borrowed(project(b)),
+            SharedCow::Owned(o) => SharedCow::Owned(project(o.borrow()).to_owned()),
+            SharedCow::Shared(s) => SharedCow::Shared(Projected::new(s.clone(), project)),
+        }
+    }
+}
@@ -1,23 +1,117 @@
 //! `RcCow`.
 
+use std::rc::Rc;
 use std::sync::Arc;
 
-use crate::ArcCow;
+use crate::{ArcCow, MaybeStatic, SharedCow, SharedPointer, Sliced};
 
-def_shared_cow! {
-    #[doc = "[`Cow`][`std::borrow::Cow`] with variant with shared [`Rc`][`std::rc::Rc`] data."]
-    pub def RcCow<B>(std::rc::Rc<B>);
+/// [`Cow`][`std::borrow::Cow`] with variant with shared [`Rc`][`std::rc::Rc`] data.
+pub type RcCow<'a, B> = SharedCow<'a, B, Rc<B>>;
+
+/// [`RcCow`] which can be sliced without cloning the shared data.
+pub type SlicedRcCow<'a, B> = SharedCow<'a, B, Sliced<Rc<B>>>;
+
+/// [`RcCow`] which can point to `'static` data without cloning it.
+pub type StaticRcCow<'a, B> = SharedCow<'a, B, MaybeStatic<Rc<B>>>;
+
+def_weak_cow! {
+    #[doc = "Weak pointer to the shared data of [`RcCow`]."]
+    pub def WeakRcCow(rc::Rc) for RcCow;
+}
+
+impl<'a, B> From<Rc<B>> for RcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    fn from(s: Rc<B>) -> Self {
+        SharedCow::Shared(s)
+    }
+}
+
+/// The data is moved into the new shared data if the pointer is unique and
+/// [`SharedPointer::try_unwrap`] supports the data, and cloned otherwise, as
+/// [`ArcCow::into_rccow`] does.
+impl<'a, B> From<Arc<B>> for RcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Rc<B>: SharedPointer<B>,
+    Arc<B>: SharedPointer<B>,
+{
+    fn from(s: Arc<B>) -> Self {
+        ArcCow::Shared(s).into_rccow()
+    }
+}
+
+/// `Shared(_)` data is shared without cloning.
+impl<'a, B> From<RcCow<'a, B>> for Rc<B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Rc<B>: SharedPointer<B>,
+{
+    fn from(cow: RcCow<'a, B>) -> Self {
+        cow.into_shared()
+    }
+}
+
+/// `Shared(_)` data is moved into the new shared data if the pointer is
+/// unique and [`SharedPointer::try_unwrap`] supports the data, and cloned
+/// otherwise.
+impl<'a, B> From<ArcCow<'a, B>> for Rc<B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Rc<B>: SharedPointer<B>,
+    Arc<B>: SharedPointer<B>,
+{
+    fn from(cow: ArcCow<'a, B>) -> Self {
+        cow.into_other_shared()
+    }
 }
-impl_cow! { RcCow<B>(std::rc::Rc<B>); <A> }
 
-impl_str_like! { RcCow, std::rc::Rc<str>, str, String }
-impl_str_like! { RcCow, std::rc::Rc<std::path::Path>, std::path::Path, std::path::PathBuf }
-impl_str_like! { RcCow, std::rc::Rc<std::ffi::OsStr>, std::ffi::OsStr, std::ffi::OsString }
+impl_c_str_conversions! { RcCow, Rc }
+impl_str_conversions! { RcCow, Rc }
+
+impl<'a> std::ops::Add<Rc<str>> for RcCow<'a, str> {
+    type Output = RcCow<'a, str>;
+
+    fn add(mut self, rhs: Rc<str>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<'a> std::ops::AddAssign<Rc<str>> for RcCow<'a, str> {
+    fn add_assign(&mut self, rhs: Rc<str>) {
+        if !self.is_owned() && self.is_empty() {
+            *self = RcCow::Shared(rhs)
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
+
+/// The data of `rhs` is copied, because it cannot be shared by [`RcCow`].
+impl<'a> std::ops::Add<Arc<str>> for RcCow<'a, str> {
+    type Output = RcCow<'a, str>;
+
+    fn add(mut self, rhs: Arc<str>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+/// The data of `rhs` is copied, because it cannot be shared by [`RcCow`].
+impl<'a> std::ops::AddAssign<Arc<str>> for RcCow<'a, str> {
+    fn add_assign(&mut self, rhs: Arc<str>) {
+        if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
 
 impl<'a, B> RcCow<'a, B>
 where
     B: 'a + ToOwned + ?Sized,
-    for<'b> &'b B: Into<Arc<B>>,
+    Arc<B>: SharedPointer<B>,
 {
     /// Creates an [`ArcCow`] value.
     #[must_use]
@@ -26,10 +120,41 @@ where
         match *self {
             RcCow::Borrowed(b) => ArcCow::Borrowed(b),
             RcCow::Owned(ref o) => ArcCow::Owned(o.borrow().to_owned()),
-            RcCow::Shared(ref s) => {
-                let b: &B = s.borrow();
-                ArcCow::Shared(b.into())
-            },
+            RcCow::Shared(ref s) => ArcCow::Shared(SharedPointer::from_borrowed(&**s)),
+        }
+    }
+
+    /// Converts the value into an [`ArcCow`] value.
+    ///
+    /// Unlike [`to_arccow`][`RcCow::to_arccow`], `Owned(_)` data is moved
+    /// without cloning.
+    /// `Shared(_)` data is moved into a new [`Arc`][`std::sync::Arc`] without
+    /// cloning if the pointer is unique and [`SharedPointer::try_unwrap`]
+    /// supports the data (such as sized data), and cloned otherwise, because
+    /// [`Rc`][`std::rc::Rc`] allocation cannot be reused by [`Arc`][`std::sync::Arc`].
+    #[must_use]
+    pub fn into_arccow(self) -> ArcCow<'a, B>
+    where
+        Rc<B>: SharedPointer<B>,
+    {
+        match self {
+            RcCow::Borrowed(b) => ArcCow::Borrowed(b),
+            RcCow::Owned(o) => ArcCow::Owned(o),
+            RcCow::Shared(s) => ArcCow::Shared(match SharedPointer::try_unwrap(s) {
+                Ok(o) => SharedPointer::from_owned(o),
+                Err(s) => SharedPointer::from_borrowed(&*s),
+            }),
         }
     }
 }
+
+impl<'a, B> From<ArcCow<'a, B>> for RcCow<'a, B>
+where
+    B: 'a + ToOwned + ?Sized,
+    Arc<B>: SharedPointer<B>,
+    Rc<B>: SharedPointer<B>,
+{
+    fn from(cow: ArcCow<'a, B>) -> Self {
+        cow.into_rccow()
+    }
+}
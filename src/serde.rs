@@ -0,0 +1,552 @@
+//! Serde support.
+//!
+//! Shared cows of `str`, `[T]`, `Path`, `OsStr`, `CStr` and sized types are
+//! serialized as their borrowed type `B`, so `[u8]` is serialized as a
+//! sequence like [`Cow`][`std::borrow::Cow`].
+//! Use [`bytes`] to serialize byte slices as bytes.
+//!
+//! Shared cows of `str`, `[T]`, `Path` and `OsStr` are deserialized as
+//! `Owned(_)` value by default, like [`Cow`][`std::borrow::Cow`], so the value
+//! with any lifetime (including `'static`) can be deserialized from any
+//! deserializer.
+//!
+//! To deserialize the values differently, use the modules below with
+//! `#[serde(with = "...")]` attribute:
+//!
+//! * [`borrow`]: as `Borrowed(_)` value if the deserializer can lend the data,
+//!   or as `Owned(_)` value otherwise.
+//! * [`shared`]: always as `Shared(_)` value.
+//! * [`borrow_or_shared`]: as `Borrowed(_)` value if possible, or as
+//!   `Shared(_)` value otherwise.
+//! * [`identity`]: as `Owned(_)` or `Shared(_)` value, preserving the sharing
+//!   of the data.
+//! * [`bytes`]: byte slices as bytes, and as `Owned(_)` value.
+//!
+//! Note that the borrowing modules also require `#[serde(borrow)]` attribute,
+//! and the borrowing fields cannot be deserialized from readers.
+//!
+//! To deserialize the values through the interners, use [`InternSeed`].
+
+use std::borrow::Cow;
+use std::ffi::{CStr, OsStr, OsString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::SharedCow;
+
+pub use self::intern::InternSeed;
+
+pub mod identity;
+mod intern;
+
+/// Shared cows which can be deserialized borrowing the data from the
+/// deserializer.
+///
+/// This is used by [`borrow`] and [`borrow_or_shared`] modules, and is
+/// implemented for shared cows of `str`, `[u8]`, `Path` and `OsStr`.
+pub trait DeserializeBorrowed<'de>: Sized {
+    /// Deserializes the value as `Borrowed(_)` if the deserializer can lend
+    /// the data, and as `Owned(_)` otherwise.
+    fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+macro_rules! impl_serialize {
+    ($($borrowed:ty),*) => {
+        $(
+            impl<'a, P> Serialize for SharedCow<'a, $borrowed, P>
+            where
+                P: Deref<Target = $borrowed>,
+            {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    (**self).serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize! { str, Path, OsStr, CStr }
+
+impl<'a, T, P> Serialize for SharedCow<'a, T, P>
+where
+    T: Clone + Serialize,
+    P: Deref<Target = T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Slices are serialized as sequences, like [`Cow`][`std::borrow::Cow`].
+/// Use [`bytes`] to serialize byte slices as bytes.
+impl<'a, T, P> Serialize for SharedCow<'a, [T], P>
+where
+    T: Clone + Serialize,
+    P: Deref<Target = [T]>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Visitor which deserializes the data borrowing it if possible.
+struct CowVisitor<'a, B: ?Sized>(PhantomData<&'a B>);
+
+impl<'a, B: ?Sized> CowVisitor<'a, B> {
+    /// Creates a new visitor.
+    fn new() -> Self {
+        CowVisitor(PhantomData)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for CowVisitor<'a, str> {
+    type Value = Cow<'a, str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        std::str::from_utf8(v)
+            .map(Cow::Borrowed)
+            .map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        std::str::from_utf8(v)
+            .map(|s| Cow::Owned(s.to_owned()))
+            .map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        String::from_utf8(v)
+            .map(Cow::Owned)
+            .map_err(|e| E::invalid_value(Unexpected::Bytes(e.as_bytes()), &self))
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for CowVisitor<'a, [u8]> {
+    type Value = Cow<'a, [u8]>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte array")
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v.as_bytes()))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.as_bytes().to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.into_bytes()))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Do not trust the size hint too much.
+        let capacity = std::cmp::min(seq.size_hint().unwrap_or(0), 4096);
+        let mut bytes = Vec::with_capacity(capacity);
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+impl<'de: 'a, 'a> DeserializeSeed<'de> for CowVisitor<'a, [u8]> {
+    type Value = Cow<'a, [u8]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de, 'a, P> Deserialize<'de> for SharedCow<'a, str, P> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(SharedCow::Owned)
+    }
+}
+
+impl<'de: 'a, 'a, P> DeserializeBorrowed<'de> for SharedCow<'a, str, P> {
+    fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_str(CowVisitor::<str>::new())
+            .map(From::from)
+    }
+}
+
+impl<'de, 'a, T, P> Deserialize<'de> for SharedCow<'a, [T], P>
+where
+    T: Clone + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(SharedCow::Owned)
+    }
+}
+
+impl<'de: 'a, 'a, P> DeserializeBorrowed<'de> for SharedCow<'a, [u8], P> {
+    fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CowVisitor::<[u8]>::new()
+            .deserialize(deserializer)
+            .map(From::from)
+    }
+}
+
+impl<'de, 'a, P> Deserialize<'de> for SharedCow<'a, Path, P> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        PathBuf::deserialize(deserializer).map(SharedCow::Owned)
+    }
+}
+
+/// Paths are serialized as strings.
+impl<'de: 'a, 'a, P> DeserializeBorrowed<'de> for SharedCow<'a, Path, P> {
+    fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = deserializer.deserialize_str(CowVisitor::<str>::new())?;
+        Ok(match s {
+            Cow::Borrowed(s) => SharedCow::Borrowed(Path::new(s)),
+            Cow::Owned(s) => SharedCow::Owned(PathBuf::from(s)),
+        })
+    }
+}
+
+impl<'de, 'a, P> Deserialize<'de> for SharedCow<'a, OsStr, P> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        OsString::deserialize(deserializer).map(SharedCow::Owned)
+    }
+}
+
+#[cfg(unix)]
+mod os_str {
+    use std::borrow::Cow;
+    use std::ffi::{OsStr, OsString};
+    use std::fmt;
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    use serde::de::{self, Deserializer, EnumAccess, Unexpected, VariantAccess, Visitor};
+    use serde::Deserialize;
+
+    use super::{CowVisitor, DeserializeBorrowed};
+    use crate::SharedCow;
+
+    /// Variants of OS strings.
+    const VARIANTS: &[&str] = &["Unix", "Windows"];
+
+    /// Variant of OS strings.
+    enum Variant {
+        /// Unix OS string.
+        Unix,
+        /// Windows OS string.
+        Windows,
+    }
+
+    impl<'de> Deserialize<'de> for Variant {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_identifier(VariantVisitor)
+        }
+    }
+
+    /// Visitor which deserializes the variant of OS strings.
+    struct VariantVisitor;
+
+    impl<'de> Visitor<'de> for VariantVisitor {
+        type Value = Variant;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("`Unix` or `Windows`")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            match v {
+                0 => Ok(Variant::Unix),
+                1 => Ok(Variant::Windows),
+                _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+            }
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            match v {
+                "Unix" => Ok(Variant::Unix),
+                "Windows" => Ok(Variant::Windows),
+                _ => Err(E::unknown_variant(v, VARIANTS)),
+            }
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            match v {
+                b"Unix" => Ok(Variant::Unix),
+                b"Windows" => Ok(Variant::Windows),
+                _ => Err(E::unknown_variant(&String::from_utf8_lossy(v), VARIANTS)),
+            }
+        }
+    }
+
+    /// Visitor which deserializes OS strings borrowing them if possible.
+    struct OsStrVisitor;
+
+    impl<'de> Visitor<'de> for OsStrVisitor {
+        type Value = Cow<'de, OsStr>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("an OS string")
+        }
+
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where
+            A: EnumAccess<'de>,
+        {
+            match data.variant()? {
+                (Variant::Unix, v) => Ok(match v.newtype_variant_seed(CowVisitor::new())? {
+                    Cow::Borrowed(bytes) => Cow::Borrowed(OsStr::from_bytes(bytes)),
+                    Cow::Owned(bytes) => Cow::Owned(OsString::from_vec(bytes)),
+                }),
+                (Variant::Windows, _) => Err(de::Error::custom(
+                    "cannot deserialize Windows OS string on Unix",
+                )),
+            }
+        }
+    }
+
+    /// OS strings are serialized as the platform-specific enum by serde.
+    impl<'de: 'a, 'a, P> DeserializeBorrowed<'de> for SharedCow<'a, OsStr, P> {
+        fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_enum("OsString", VARIANTS, OsStrVisitor)
+                .map(From::from)
+        }
+    }
+}
+
+/// OS strings are always deserialized as `Owned(_)` value on Windows.
+#[cfg(windows)]
+impl<'de: 'a, 'a, P> DeserializeBorrowed<'de> for SharedCow<'a, OsStr, P> {
+    fn deserialize_borrowed<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Self as Deserialize<'de>>::deserialize(deserializer)
+    }
+}
+
+/// (De)serialization as `Borrowed(_)` or `Owned(_)` value.
+///
+/// Use this module with `#[serde(borrow, with = "shared_cow::serde::borrow")]`
+/// to deserialize the value as `Borrowed(_)` when the deserializer can lend
+/// the data.
+pub mod borrow {
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::DeserializeBorrowed;
+    use crate::SharedCow;
+
+    /// Serializes the value as its borrowed type `B`.
+    pub fn serialize<'a, B, P, S>(
+        cow: &SharedCow<'a, B, P>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        SharedCow<'a, B, P>: Serialize,
+        S: Serializer,
+    {
+        cow.serialize(serializer)
+    }
+
+    /// Deserializes the value as `Borrowed(_)` if possible, or as `Owned(_)`
+    /// otherwise.
+    pub fn deserialize<'de, 'a, B, P, D>(deserializer: D) -> Result<SharedCow<'a, B, P>, D::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        SharedCow<'a, B, P>: DeserializeBorrowed<'de>,
+        D: Deserializer<'de>,
+    {
+        SharedCow::deserialize_borrowed(deserializer)
+    }
+}
+
+/// (De)serialization always as `Shared(_)` value.
+///
+/// Use this module with `#[serde(with = "shared_cow::serde::shared")]`.
+/// This does not borrow the data, so the value with any lifetime (including
+/// `'static`) can be deserialized from any deserializer.
+pub mod shared {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{SharedCow, SharedPointer};
+
+    /// Serializes the value as its borrowed type `B`.
+    pub fn serialize<'a, B, P, S>(
+        cow: &SharedCow<'a, B, P>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        SharedCow<'a, B, P>: Serialize,
+        S: Serializer,
+    {
+        cow.serialize(serializer)
+    }
+
+    /// Deserializes the value as `Shared(_)`.
+    pub fn deserialize<'de, 'a, B, P, D>(deserializer: D) -> Result<SharedCow<'a, B, P>, D::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        P: SharedPointer<B>,
+        SharedCow<'a, B, P>: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        SharedCow::deserialize(deserializer).map(|cow| SharedCow::Shared(cow.into_shared()))
+    }
+}
+
+/// (De)serialization as `Borrowed(_)` or `Shared(_)` value.
+///
+/// Use this module with
+/// `#[serde(borrow, with = "shared_cow::serde::borrow_or_shared")]` to
+/// deserialize the value as `Shared(_)` instead of `Owned(_)` when the
+/// deserializer cannot lend the data.
+pub mod borrow_or_shared {
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::DeserializeBorrowed;
+    use crate::{SharedCow, SharedPointer};
+
+    /// Serializes the value as its borrowed type `B`.
+    pub fn serialize<'a, B, P, S>(
+        cow: &SharedCow<'a, B, P>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        SharedCow<'a, B, P>: Serialize,
+        S: Serializer,
+    {
+        cow.serialize(serializer)
+    }
+
+    /// Deserializes the value as `Borrowed(_)` if possible, or as `Shared(_)`
+    /// otherwise.
+    pub fn deserialize<'de, 'a, B, P, D>(deserializer: D) -> Result<SharedCow<'a, B, P>, D::Error>
+    where
+        B: 'a + ToOwned + ?Sized,
+        P: SharedPointer<B>,
+        SharedCow<'a, B, P>: DeserializeBorrowed<'de>,
+        D: Deserializer<'de>,
+    {
+        SharedCow::deserialize_borrowed(deserializer).map(|cow| match cow {
+            SharedCow::Owned(o) => SharedCow::Shared(P::from_owned(o)),
+            cow => cow,
+        })
+    }
+}
+
+/// (De)serialization of byte slices as bytes.
+///
+/// Use this module with `#[serde(with = "shared_cow::serde::bytes")]` to
+/// serialize the value with [`Serializer::serialize_bytes`], which is more
+/// compact than the sequence of integers in some formats.
+/// The value is deserialized as `Owned(_)`, like
+/// [`Cow`][`std::borrow::Cow`] with `serde_bytes`.
+/// Sequences of integers are also accepted on deserialization.
+pub mod bytes {
+    use std::ops::Deref;
+
+    use serde::de::{DeserializeSeed, Deserializer};
+    use serde::Serializer;
+
+    use super::CowVisitor;
+    use crate::SharedCow;
+
+    /// Serializes the value as bytes.
+    pub fn serialize<P, S>(cow: &SharedCow<'_, [u8], P>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: Deref<Target = [u8]>,
+        S: Serializer,
+    {
+        serializer.serialize_bytes(cow)
+    }
+
+    /// Deserializes the value as `Owned(_)`.
+    pub fn deserialize<'de, 'a, P, D>(deserializer: D) -> Result<SharedCow<'a, [u8], P>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CowVisitor::<'de, [u8]>::new()
+            .deserialize(deserializer)
+            .map(|cow| SharedCow::Owned(cow.into_owned()))
+    }
+}
@@ -0,0 +1,303 @@
+//! (De)serialization preserving the identity of the shared data.
+//!
+//! Use this module with `#[serde(with = "shared_cow::serde::identity")]` on the
+//! fields, and (de)serialize the whole data through [`Preserved`] and
+//! [`PreservedSeed`].
+//! Then the shared data pointed by multiple `Shared(_)` values is serialized
+//! only once, and the deserialized `Shared(_)` values share the data again.
+//!
+//! The seen shared data is recorded to the thread-local table while
+//! [`Preserved`] or [`PreservedSeed`] is running.
+//! Outside of them, `Shared(_)` values are serialized without the sharing
+//! information, and the references to the shared data cannot be deserialized.
+//!
+//! The values are always deserialized as `Owned(_)` or `Shared(_)`, so the
+//! value with any lifetime can be deserialized from any deserializer.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::thread::LocalKey;
+
+use serde::de::{self, DeserializeSeed, EnumAccess, Unexpected, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{SharedCow, SharedPointer};
+
+/// Name of the enum to (de)serialize the values.
+const NAME: &str = "SharedCow";
+
+/// Variants of the enum to (de)serialize the values.
+const VARIANTS: &[&str] = &["Plain", "Def", "Ref"];
+
+/// Identity of the shared data: type of the pointer, address and size.
+type Key = (TypeId, usize, usize);
+
+/// IDs of the shared data seen by the serializer.
+type SeenTable = HashMap<Key, u64>;
+
+/// Shared data created by the deserializer, indexed by the ID.
+///
+/// `None` is the placeholder for the data being deserialized.
+type CreatedTable = Vec<Option<Box<dyn Any>>>;
+
+thread_local! {
+    /// Table for the running serializer.
+    static SEEN: RefCell<Option<SeenTable>> = const { RefCell::new(None) };
+    /// Table for the running deserializer.
+    static CREATED: RefCell<Option<CreatedTable>> = const { RefCell::new(None) };
+}
+
+/// Guard which restores the previous table when dropped.
+struct Scope<T: 'static> {
+    /// Table.
+    table: &'static LocalKey<RefCell<Option<T>>>,
+    /// Previous table.
+    prev: Option<T>,
+}
+
+impl<T: 'static> Scope<T> {
+    /// Sets the new table until the guard is dropped.
+    fn enter(table: &'static LocalKey<RefCell<Option<T>>>, new: T) -> Self {
+        let prev = table.with(|table| table.replace(Some(new)));
+        Scope { table, prev }
+    }
+}
+
+impl<T: 'static> Drop for Scope<T> {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        self.table.with(|table| *table.borrow_mut() = prev);
+    }
+}
+
+/// Wrapper to serialize the data preserving the identity of the shared data.
+///
+/// See [the module documentation](self) for details.
+pub struct Preserved<'t, T: ?Sized>(&'t T);
+
+impl<'t, T: ?Sized> Preserved<'t, T> {
+    /// Creates a new wrapper.
+    pub fn new(value: &'t T) -> Self {
+        Preserved(value)
+    }
+}
+
+impl<'t, T> Serialize for Preserved<'t, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let _scope = Scope::enter(&SEEN, HashMap::new());
+        self.0.serialize(serializer)
+    }
+}
+
+/// Seed to deserialize the data serialized by [`Preserved`].
+///
+/// See [the module documentation](self) for details.
+pub struct PreservedSeed<T>(PhantomData<fn() -> T>);
+
+impl<T> PreservedSeed<T> {
+    /// Creates a new seed.
+    pub fn new() -> Self {
+        PreservedSeed(PhantomData)
+    }
+}
+
+impl<T> Default for PreservedSeed<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for PreservedSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _scope = Scope::enter(&CREATED, Vec::new());
+        T::deserialize(deserializer)
+    }
+}
+
+/// Serializes the value, or the reference to the shared data already
+/// serialized.
+pub fn serialize<B, P, S>(cow: &SharedCow<'_, B, P>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    B: ToOwned + ?Sized + Serialize,
+    P: 'static + Deref<Target = B>,
+    S: Serializer,
+{
+    let shared = match cow {
+        SharedCow::Shared(shared) => shared,
+        _ => return serializer.serialize_newtype_variant(NAME, 0, "Plain", &**cow),
+    };
+    let key = (
+        TypeId::of::<P>(),
+        &**shared as *const B as *const () as usize,
+        mem::size_of_val(&**shared),
+    );
+    // Do not keep the table borrowed while serializing the data, which may
+    // contain other values.
+    let seen = SEEN.with(|table| {
+        table.borrow_mut().as_mut().map(|table| {
+            let id = table.len() as u64;
+            match table.entry(key) {
+                Entry::Occupied(entry) => Some(*entry.get()),
+                Entry::Vacant(entry) => {
+                    entry.insert(id);
+                    None
+                },
+            }
+        })
+    });
+    match seen {
+        None => serializer.serialize_newtype_variant(NAME, 0, "Plain", &**cow),
+        Some(None) => serializer.serialize_newtype_variant(NAME, 1, "Def", &**cow),
+        Some(Some(id)) => serializer.serialize_newtype_variant(NAME, 2, "Ref", &id),
+    }
+}
+
+/// Deserializes the value, sharing the data with the other values deserialized
+/// in the same [`PreservedSeed`].
+pub fn deserialize<'de, 'a, B, P, D>(deserializer: D) -> Result<SharedCow<'a, B, P>, D::Error>
+where
+    B: 'a + ToOwned + ?Sized,
+    <B as ToOwned>::Owned: Deserialize<'de>,
+    P: 'static + SharedPointer<B>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_enum(NAME, VARIANTS, CowVisitor(PhantomData))
+}
+
+/// Variant of the enum to (de)serialize the values.
+enum Variant {
+    /// Value which does not share the data.
+    Plain,
+    /// Shared data serialized first time.
+    Def,
+    /// Reference to the shared data already serialized.
+    Ref,
+}
+
+impl<'de> Deserialize<'de> for Variant {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(VariantVisitor)
+    }
+}
+
+/// Visitor which deserializes the variant.
+struct VariantVisitor;
+
+impl<'de> Visitor<'de> for VariantVisitor {
+    type Value = Variant;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`Plain`, `Def` or `Ref`")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        match v {
+            0 => Ok(Variant::Plain),
+            1 => Ok(Variant::Def),
+            2 => Ok(Variant::Ref),
+            _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            "Plain" => Ok(Variant::Plain),
+            "Def" => Ok(Variant::Def),
+            "Ref" => Ok(Variant::Ref),
+            _ => Err(E::unknown_variant(v, VARIANTS)),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match v {
+            b"Plain" => Ok(Variant::Plain),
+            b"Def" => Ok(Variant::Def),
+            b"Ref" => Ok(Variant::Ref),
+            _ => Err(E::unknown_variant(&String::from_utf8_lossy(v), VARIANTS)),
+        }
+    }
+}
+
+/// Visitor which deserializes the value.
+struct CowVisitor<'a, B: ?Sized, P>(PhantomData<fn() -> (&'a B, P)>);
+
+impl<'de, 'a, B, P> Visitor<'de> for CowVisitor<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    <B as ToOwned>::Owned: Deserialize<'de>,
+    P: 'static + SharedPointer<B>,
+{
+    type Value = SharedCow<'a, B, P>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a shared cow")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        match data.variant()? {
+            (Variant::Plain, v) => v.newtype_variant().map(SharedCow::Owned),
+            (Variant::Def, v) => {
+                // Reserve the ID before deserializing the data, which may
+                // contain other values.
+                let id = CREATED.with(|table| {
+                    table.borrow_mut().as_mut().map(|table| {
+                        table.push(None);
+                        table.len() - 1
+                    })
+                });
+                let shared = P::from_owned(v.newtype_variant()?);
+                if let Some(id) = id {
+                    CREATED.with(|table| {
+                        if let Some(table) = table.borrow_mut().as_mut() {
+                            table[id] = Some(Box::new(shared.clone()));
+                        }
+                    });
+                }
+                Ok(SharedCow::Shared(shared))
+            },
+            (Variant::Ref, v) => {
+                let id: u64 = v.newtype_variant()?;
+                let shared = CREATED.with(|table| {
+                    let table = table.borrow();
+                    let table = table.as_ref().ok_or_else(|| {
+                        de::Error::custom("shared data reference outside of `PreservedSeed`")
+                    })?;
+                    table
+                        .get(id as usize)
+                        .and_then(Option::as_ref)
+                        .and_then(|shared| shared.downcast_ref::<P>())
+                        .cloned()
+                        .ok_or_else(|| {
+                            de::Error::invalid_value(Unexpected::Unsigned(id), &"a shared data ID")
+                        })
+                })?;
+                Ok(SharedCow::Shared(shared))
+            },
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Deserialization through the interners.
+
+use std::path::Path;
+
+use serde::de::{DeserializeSeed, Deserializer};
+
+use super::DeserializeBorrowed;
+use crate::{ArcCow, ArcInterner, RcCow, RcInterner};
+
+/// Seed to deserialize the value as `Shared(_)` value through the interner.
+///
+/// The deserialized value shares the data with the equal value already
+/// interned, and new data is interned for the later values.
+/// If the equal value is already interned, the data is not cloned even when
+/// the deserializer cannot lend it.
+///
+/// This is implemented for [`ArcInterner`] and [`RcInterner`] of `str`,
+/// `[u8]`, `Path` and `OsStr`.
+pub struct InternSeed<'i, I: ?Sized>(&'i I);
+
+impl<'i, I: ?Sized> InternSeed<'i, I> {
+    /// Creates a new seed.
+    pub fn new(interner: &'i I) -> Self {
+        InternSeed(interner)
+    }
+}
+
+impl<'i, I: ?Sized> Clone for InternSeed<'i, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'i, I: ?Sized> Copy for InternSeed<'i, I> {}
+
+macro_rules! impl_intern_seed {
+    ($interner:ident, $cow:ident, $($borrowed:ty),*) => {
+        $(
+            impl<'de, 'i> DeserializeSeed<'de> for InternSeed<'i, $interner<$borrowed>> {
+                type Value = $cow<'static, $borrowed>;
+
+                fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    $cow::<'de, $borrowed>::deserialize_borrowed(deserializer)
+                        .map(|cow| self.0.intern(cow))
+                }
+            }
+        )*
+    };
+}
+
+impl_intern_seed! { ArcInterner, ArcCow, str, [u8], Path }
+impl_intern_seed! { RcInterner, RcCow, str, [u8], Path }
+#[cfg(any(unix, windows))]
+impl_intern_seed! { ArcInterner, ArcCow, std::ffi::OsStr }
+#[cfg(any(unix, windows))]
+impl_intern_seed! { RcInterner, RcCow, std::ffi::OsStr }
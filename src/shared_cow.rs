@@ -0,0 +1,936 @@
+//! `SharedCow`.
+
+use std::ops::Deref;
+
+use crate::SharedPointer;
+
+/// [`Cow`][`std::borrow::Cow`] with variant with shared data.
+///
+/// `P` is the type of the shared pointer, such as [`Rc<B>`][`std::rc::Rc`]
+/// and [`Arc<B>`][`std::sync::Arc`].
+///
+/// The conversions between `str`, `OsStr` and `Path` values, such as
+/// `into_path` and `into_string`, clone `Shared(_)` data into the new shared
+/// data, because std provides no conversions between the shared pointers of
+/// these types.
+pub enum SharedCow<'a, B, P>
+where
+    B: ToOwned + ?Sized,
+{
+    /// Borrowed data.
+    Borrowed(&'a B),
+    /// Owned data.
+    Owned(<B as ToOwned>::Owned),
+    /// Shared data.
+    Shared(P),
+}
+
+/// Variant of a shared cow value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CowKind {
+    /// Borrowed data.
+    Borrowed,
+    /// Owned data.
+    Owned,
+    /// Shared data.
+    Shared,
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    /// Returns the variant of the value.
+    pub fn kind(&self) -> CowKind {
+        match self {
+            SharedCow::Borrowed(_) => CowKind::Borrowed,
+            SharedCow::Owned(_) => CowKind::Owned,
+            SharedCow::Shared(_) => CowKind::Shared,
+        }
+    }
+
+    /// Returns `true` if the value is `Borrowed(_)`.
+    pub fn is_borrowed(&self) -> bool {
+        self.kind() == CowKind::Borrowed
+    }
+
+    /// Returns `true` if the value is `Owned(_)`.
+    pub fn is_owned(&self) -> bool {
+        self.kind() == CowKind::Owned
+    }
+
+    /// Returns `true` if the value is `Shared(_)`.
+    pub fn is_shared(&self) -> bool {
+        self.kind() == CowKind::Shared
+    }
+
+    /// Returns the reference to the shared pointer if the value is `Shared(_)`.
+    pub fn as_shared(&self) -> Option<&P> {
+        match self {
+            SharedCow::Shared(shared) => Some(shared),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    /// Creates a new owned value.
+    ///
+    /// This always clones the value.
+    #[must_use]
+    pub fn to_owned(&self) -> <B as ToOwned>::Owned {
+        use std::borrow::Borrow;
+        let b: &B = self.borrow();
+        b.to_owned()
+    }
+
+    /// Creates a new owned value, cloning `Shared(_)` data even if the
+    /// pointer is unique.
+    ///
+    /// This is used for unsized data, which [`SharedPointer::try_unwrap`]
+    /// never moves out.
+    pub(crate) fn into_owned_cloned(self) -> <B as ToOwned>::Owned {
+        match self {
+            SharedCow::Owned(owned) => owned,
+            cow => cow.to_owned(),
+        }
+    }
+
+    /// Returns a borrowed [`Cow`][`std::borrow::Cow`] pointing to the data.
+    ///
+    /// This never clones the value.
+    pub fn as_cow(&self) -> std::borrow::Cow<'_, B> {
+        std::borrow::Cow::Borrowed(self)
+    }
+
+    /// Returns a `Borrowed(_)` value pointing to the data.
+    ///
+    /// This never clones the value.
+    pub fn reborrow(&self) -> SharedCow<'_, B, P> {
+        SharedCow::Borrowed(self)
+    }
+
+    /// Detaches the value from the borrow lifetime.
+    ///
+    /// `Owned(_)` and `Shared(_)` are kept as is, and `Borrowed(_)` is turned
+    /// into `Owned(_)`.
+    /// See also [`into_static`][`SharedCow::into_static`].
+    #[must_use]
+    pub fn into_static_owned(self) -> SharedCow<'static, B, P>
+    where
+        B: 'static,
+    {
+        match self {
+            SharedCow::Borrowed(borrowed) => SharedCow::Owned(borrowed.to_owned()),
+            SharedCow::Owned(owned) => SharedCow::Owned(owned),
+            SharedCow::Shared(shared) => SharedCow::Shared(shared),
+        }
+    }
+
+    /// Returns `true` if the two values point to the same data in memory.
+    ///
+    /// `Shared(_)` values are compared by the data their pointers point to.
+    /// Otherwise the addresses of the data are compared, so that a
+    /// `Borrowed(_)` value borrowing from the other value is also equal.
+    /// Empty or zero-sized data of `Borrowed(_)` and `Owned(_)` values never
+    /// compares equal, because unrelated values can have the same dangling
+    /// address.
+    pub fn ptr_eq<Q>(&self, other: &SharedCow<'_, B, Q>) -> bool
+    where
+        Q: Deref<Target = B>,
+    {
+        match (self, other) {
+            (SharedCow::Shared(lhs), SharedCow::Shared(rhs)) => std::ptr::eq::<B>(&**lhs, &**rhs),
+            _ => std::mem::size_of_val::<B>(self) != 0 && std::ptr::eq::<B>(&**self, &**other),
+        }
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: SharedPointer<B>,
+{
+    /// Creates a new shared value.
+    ///
+    /// This clones the value if necessary.
+    #[must_use]
+    pub fn into_shared(self) -> P {
+        match self {
+            SharedCow::Borrowed(borrowed) => P::from_borrowed(borrowed),
+            SharedCow::Owned(owned) => P::from_owned(owned),
+            SharedCow::Shared(shared) => shared,
+        }
+    }
+
+    /// Creates a new shared value of another shared pointer type.
+    ///
+    /// `Shared(_)` data is moved out if [`SharedPointer::try_unwrap`]
+    /// succeeds, and cloned otherwise.
+    pub(crate) fn into_other_shared<Q>(self) -> Q
+    where
+        Q: SharedPointer<B>,
+    {
+        match self {
+            SharedCow::Borrowed(borrowed) => Q::from_borrowed(borrowed),
+            SharedCow::Owned(owned) => Q::from_owned(owned),
+            SharedCow::Shared(shared) => match P::try_unwrap(shared) {
+                Ok(owned) => Q::from_owned(owned),
+                Err(shared) => Q::from_borrowed(&*shared),
+            },
+        }
+    }
+
+    /// Creates a new shared value.
+    ///
+    /// This always clones the value.
+    #[must_use]
+    pub fn to_shared(&self) -> P {
+        use std::borrow::Borrow;
+        match self {
+            SharedCow::Borrowed(borrowed) => P::from_borrowed(borrowed),
+            SharedCow::Owned(owned) => P::from_borrowed(owned.borrow()),
+            SharedCow::Shared(shared) => Clone::clone(shared),
+        }
+    }
+
+    /// Creates a new owned value.
+    ///
+    /// This behaves like [`Cow::into_owned`][`std::borrow::Cow::into_owned`].
+    /// This clones the value if necessary.
+    ///
+    /// `Shared(_)` data is moved out without cloning if the pointer is unique
+    /// and [`SharedPointer::try_unwrap`] supports the data (such as sized
+    /// data in `Rc` and `Arc`), and cloned otherwise.
+    #[must_use]
+    pub fn into_owned(self) -> <B as ToOwned>::Owned {
+        match self {
+            SharedCow::Borrowed(borrowed) => borrowed.to_owned(),
+            SharedCow::Owned(owned) => owned,
+            SharedCow::Shared(shared) => {
+                P::try_unwrap(shared).unwrap_or_else(|shared| (*shared).to_owned())
+            },
+        }
+    }
+
+    /// Returns mutable reference to the `Owned(_)` value.
+    ///
+    /// This behaves like [`Cow::to_mut`][`std::borrow::Cow::to_mut`].
+    /// This clones the value if necessary.
+    ///
+    /// If `self` is already `Owned(_)`, the value is returned in place
+    /// and nothing is cloned.
+    /// `Shared(_)` data is moved out without cloning if the pointer is unique
+    /// and [`SharedPointer::try_unwrap`] supports the data (such as sized
+    /// data in `Rc` and `Arc`), and cloned otherwise.
+    pub fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned {
+        if self.is_shared() {
+            replace_with(self, SharedCow::unwrap_shared);
+        }
+        match *self {
+            SharedCow::Borrowed(borrowed) => *self = SharedCow::Owned(borrowed.to_owned()),
+            SharedCow::Shared(ref shared) => {
+                let owned = (**shared).to_owned();
+                *self = SharedCow::Owned(owned);
+            },
+            SharedCow::Owned(_) => {},
+        }
+        match *self {
+            SharedCow::Owned(ref mut owned) => owned,
+            _ => unreachable!("Should never happen because `*self` must be `Owned` variant"),
+        }
+    }
+
+    /// Turns `Shared(_)` into `Owned(_)` if the data can be moved out of the
+    /// pointer without cloning.
+    pub(crate) fn unwrap_shared(self) -> Self {
+        match self {
+            SharedCow::Shared(shared) => match P::try_unwrap(shared) {
+                Ok(owned) => SharedCow::Owned(owned),
+                Err(shared) => SharedCow::Shared(shared),
+            },
+            cow => cow,
+        }
+    }
+
+    /// Detaches the value from the borrow lifetime.
+    ///
+    /// `Owned(_)` and `Shared(_)` are kept as is, and `Borrowed(_)` is turned
+    /// into `Shared(_)`.
+    /// See also [`into_static_owned`][`SharedCow::into_static_owned`].
+    #[must_use]
+    pub fn into_static(self) -> SharedCow<'static, B, P>
+    where
+        B: 'static,
+    {
+        match self {
+            SharedCow::Owned(owned) => SharedCow::Owned(owned),
+            SharedCow::Shared(shared) => SharedCow::Shared(shared),
+            borrowed @ SharedCow::Borrowed(_) => SharedCow::Shared(borrowed.into_shared()),
+        }
+    }
+
+    /// Creates a new value detached from the borrow lifetime.
+    ///
+    /// `Owned(_)` is cloned, `Shared(_)` shares the data, and `Borrowed(_)`
+    /// is turned into `Shared(_)`.
+    #[must_use]
+    pub fn to_static(&self) -> SharedCow<'static, B, P>
+    where
+        B: 'static,
+    {
+        use std::borrow::Borrow;
+        match self {
+            SharedCow::Owned(owned) => SharedCow::Owned(owned.borrow().to_owned()),
+            _ => SharedCow::Shared(self.to_shared()),
+        }
+    }
+
+    /// Returns the number of strong pointers to the data if the value is
+    /// `Shared(_)` and the data is reference counted.
+    ///
+    /// This returns `None` for the data which is not reference counted, such
+    /// as `'static` data of [`MaybeStatic`][`crate::MaybeStatic`].
+    pub fn strong_count(&self) -> Option<usize> {
+        self.counted().map(P::strong_count)
+    }
+
+    /// Returns the number of weak pointers to the data if the value is
+    /// `Shared(_)` and the data is reference counted.
+    ///
+    /// See [`strong_count`][`SharedCow::strong_count`] for the data which is
+    /// not reference counted.
+    pub fn weak_count(&self) -> Option<usize> {
+        self.counted().map(P::weak_count)
+    }
+
+    /// Returns the shared pointer if the data is reference counted.
+    fn counted(&self) -> Option<&P> {
+        self.as_shared().filter(|shared| P::strong_count(shared) != 0)
+    }
+
+    /// Turns `self` into `Borrowed` or `Shared` variant.
+    ///
+    /// This may clone the value if necessary.
+    pub fn share(&mut self) -> &mut SharedCow<'a, B, P> {
+        use std::borrow::Borrow;
+        // It is perfect if I can do `*self = SharedCow::Shared(o.into())`
+        // for `if let SharedCow::Owned(o) = *self`, but it is unable in safe
+        // manner.
+        if let SharedCow::Owned(ref o) = *self {
+            let b: &B = o.borrow();
+            *self = SharedCow::Shared(P::from_borrowed(b))
+        }
+        self
+    }
+}
+
+/// Replaces `*dest` with the value returned by `f`, which takes the current
+/// value.
+///
+/// This lets the fields be moved out of `*dest` without placeholder values.
+/// The process is aborted if `f` panics, because `*dest` is left invalid
+/// during the call.
+pub(crate) fn replace_with<T>(dest: &mut T, f: impl FnOnce(T) -> T) {
+    /// Guard aborting the process on unwinding.
+    struct AbortOnUnwind;
+
+    impl Drop for AbortOnUnwind {
+        fn drop(&mut self) {
+            std::process::abort();
+        }
+    }
+
+    let guard = AbortOnUnwind;
+    // This is safe because `*dest` is overwritten by a valid value before it
+    // is used again, and the process is aborted before unwinding if `f`
+    // panics.
+    unsafe {
+        let old = std::ptr::read(dest);
+        std::ptr::write(dest, f(old));
+    }
+    std::mem::forget(guard);
+}
+
+impl_str_like! { str, String }
+impl_str_like! { std::path::Path, std::path::PathBuf }
+impl_str_like! { std::ffi::OsStr, std::ffi::OsString }
+impl_str_like! { std::ffi::CStr, std::ffi::CString }
+
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, str }
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, &'b str }
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, String }
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, std::ffi::OsStr }
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, &'b std::ffi::OsStr }
+impl_cmp_os_str_like! { std::path::Path, SharedCow<'a, std::path::Path, P>, std::ffi::OsString }
+impl_cmp_os_str_like! { std::ffi::OsStr, SharedCow<'a, std::ffi::OsStr, P>, str }
+impl_cmp_os_str_like! { std::ffi::OsStr, SharedCow<'a, std::ffi::OsStr, P>, &'b str }
+impl_cmp_os_str_like! { std::ffi::OsStr, SharedCow<'a, std::ffi::OsStr, P>, String }
+
+impl<'a, T, P> From<&'a [T]> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: &'a [T]) -> Self {
+        SharedCow::Borrowed(v)
+    }
+}
+
+impl<'a, T, P> From<SharedCow<'a, [T], P>> for Vec<T>
+where
+    [T]: ToOwned<Owned = Vec<T>>,
+    P: Deref<Target = [T]>,
+{
+    fn from(cow: SharedCow<'a, [T], P>) -> Self {
+        cow.into_owned_cloned()
+    }
+}
+
+impl<'a, T, P> From<Vec<T>> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: Vec<T>) -> Self {
+        SharedCow::Owned(v)
+    }
+}
+
+impl<'a, T, P> From<SharedCow<'a, [T], P>> for Box<[T]>
+where
+    T: Clone,
+    P: Deref<Target = [T]>,
+{
+    fn from(cow: SharedCow<'a, [T], P>) -> Self {
+        cow.into_owned_cloned().into()
+    }
+}
+
+impl<'a, T, P> From<&'a Vec<T>> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: &'a Vec<T>) -> Self {
+        SharedCow::Borrowed(v)
+    }
+}
+
+impl<'a, T, P> From<&'a Box<[T]>> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: &'a Box<[T]>) -> Self {
+        SharedCow::Borrowed(v)
+    }
+}
+
+impl<'a, T, P> From<&'a std::rc::Rc<[T]>> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: &'a std::rc::Rc<[T]>) -> Self {
+        SharedCow::Borrowed(v)
+    }
+}
+
+impl<'a, T, P> From<&'a std::sync::Arc<[T]>> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from(v: &'a std::sync::Arc<[T]>) -> Self {
+        SharedCow::Borrowed(v)
+    }
+}
+
+impl<'a, B, P> AsRef<B> for SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    fn as_ref(&self) -> &B {
+        self
+    }
+}
+
+impl<'a, B, P> std::borrow::Borrow<B> for SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    fn borrow(&self) -> &B {
+        self
+    }
+}
+
+impl<'a, B, P> Clone for SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        use std::borrow::Borrow;
+        match self {
+            SharedCow::Borrowed(b) => SharedCow::Borrowed(b),
+            SharedCow::Owned(o) => SharedCow::Owned(o.borrow().to_owned()),
+            SharedCow::Shared(s) => SharedCow::Shared(Clone::clone(s)),
+        }
+    }
+}
+
+impl<'a, P> From<SharedCow<'a, str, P>> for Box<dyn std::error::Error>
+where
+    P: Deref<Target = str>,
+{
+    fn from(err: SharedCow<'a, str, P>) -> Self {
+        let err: String = err.into();
+        From::from(err)
+    }
+}
+
+impl<'a, 'b, P> From<SharedCow<'b, str, P>> for Box<dyn std::error::Error + Send + Sync + 'a>
+where
+    P: Deref<Target = str>,
+{
+    fn from(err: SharedCow<'b, str, P>) -> Self {
+        let err: String = err.into();
+        From::from(err)
+    }
+}
+
+impl<'a, B, P> From<SharedCow<'a, B, P>> for std::borrow::Cow<'a, B>
+where
+    B: ?Sized + ToOwned,
+    P: Deref<Target = B>,
+{
+    fn from(cow: SharedCow<'a, B, P>) -> Self {
+        match cow {
+            SharedCow::Borrowed(b) => std::borrow::Cow::Borrowed(b),
+            SharedCow::Owned(b) => std::borrow::Cow::Owned(b),
+            SharedCow::Shared(s) => std::borrow::Cow::Owned((*s).to_owned()),
+        }
+    }
+}
+
+impl<'a, B, P> From<std::borrow::Cow<'a, B>> for SharedCow<'a, B, P>
+where
+    B: ?Sized + ToOwned,
+{
+    fn from(cow: std::borrow::Cow<'a, B>) -> Self {
+        match cow {
+            std::borrow::Cow::Borrowed(b) => SharedCow::Borrowed(b),
+            std::borrow::Cow::Owned(o) => SharedCow::Owned(o),
+        }
+    }
+}
+
+/// The boxed data is moved into `Owned(_)` value without cloning.
+impl<'a, B, P> From<Box<B>> for SharedCow<'a, B, P>
+where
+    B: ?Sized + ToOwned,
+    <B as ToOwned>::Owned: From<Box<B>>,
+{
+    fn from(b: Box<B>) -> Self {
+        SharedCow::Owned(From::from(b))
+    }
+}
+
+impl<'a, B, P> std::hash::Hash for SharedCow<'a, B, P>
+where
+    B: ?Sized + std::hash::Hash + ToOwned,
+    P: Deref<Target = B>,
+{
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(&**self, state)
+    }
+}
+
+impl<'a, P> std::iter::FromIterator<char> for SharedCow<'a, str, P> {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        SharedCow::Owned(std::iter::FromIterator::from_iter(iter))
+    }
+}
+
+impl<'a, 'b, P> std::iter::FromIterator<&'b str> for SharedCow<'a, str, P> {
+    fn from_iter<I: IntoIterator<Item = &'b str>>(iter: I) -> Self {
+        SharedCow::Owned(std::iter::FromIterator::from_iter(iter))
+    }
+}
+
+impl<'a, P> std::iter::FromIterator<String> for SharedCow<'a, str, P> {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        SharedCow::Owned(std::iter::FromIterator::from_iter(iter))
+    }
+}
+
+impl<'a, P> std::iter::FromIterator<SharedCow<'a, str, P>> for String
+where
+    P: Deref<Target = str>,
+{
+    fn from_iter<I: IntoIterator<Item = SharedCow<'a, str, P>>>(iter: I) -> Self {
+        let mut buf = String::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl<'a, T, P> std::iter::FromIterator<T> for SharedCow<'a, [T], P>
+where
+    T: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SharedCow::Owned(std::iter::FromIterator::from_iter(iter))
+    }
+}
+
+impl<'a, P> Extend<SharedCow<'a, str, P>> for String
+where
+    P: Deref<Target = str>,
+{
+    fn extend<I: IntoIterator<Item = SharedCow<'a, str, P>>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl<'a, B, P> std::fmt::Debug for SharedCow<'a, B, P>
+where
+    B: std::fmt::Debug + ToOwned + ?Sized,
+    <B as ToOwned>::Owned: std::fmt::Debug,
+    P: Deref<Target = B>,
+{
+    /// With the alternate flag (`{:#?}`), the variant is also printed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return match self {
+                SharedCow::Borrowed(b) => f.debug_tuple("Borrowed").field(b).finish(),
+                SharedCow::Owned(o) => f.debug_tuple("Owned").field(o).finish(),
+                SharedCow::Shared(s) => f.debug_tuple("Shared").field(&&**s).finish(),
+            };
+        }
+        match self {
+            SharedCow::Borrowed(b) => std::fmt::Debug::fmt(b, f),
+            SharedCow::Owned(o) => std::fmt::Debug::fmt(o, f),
+            SharedCow::Shared(s) => std::fmt::Debug::fmt(&**s, f),
+        }
+    }
+}
+
+impl<'a, B, P> std::fmt::Display for SharedCow<'a, B, P>
+where
+    B: std::fmt::Display + ToOwned + ?Sized,
+    <B as ToOwned>::Owned: std::fmt::Display,
+    P: Deref<Target = B>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedCow::Borrowed(b) => std::fmt::Display::fmt(b, f),
+            SharedCow::Owned(o) => std::fmt::Display::fmt(o, f),
+            SharedCow::Shared(s) => std::fmt::Display::fmt(&**s, f),
+        }
+    }
+}
+
+impl<'a, B, P> Deref for SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    P: Deref<Target = B>,
+{
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        use std::borrow::Borrow;
+        match self {
+            SharedCow::Borrowed(borrowed) => borrowed,
+            SharedCow::Owned(owned) => owned.borrow(),
+            SharedCow::Shared(shared) => shared,
+        }
+    }
+}
+
+impl<'a, B, P> Default for SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+    <B as ToOwned>::Owned: Default,
+{
+    fn default() -> Self {
+        SharedCow::Owned(<B as ToOwned>::Owned::default())
+    }
+}
+
+impl<'a, P> SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    /// Returns mutable reference to the `Owned(_)` string with at least
+    /// `additional` bytes of spare capacity.
+    ///
+    /// This clones the value if necessary, allocating the whole capacity at
+    /// once.
+    pub(crate) fn reserve_owned(&mut self, additional: usize) -> &mut String {
+        if !self.is_owned() {
+            let mut s = String::with_capacity(self.len() + additional);
+            s.push_str(self);
+            *self = SharedCow::Owned(s);
+        }
+        let s = match *self {
+            SharedCow::Owned(ref mut s) => s,
+            _ => unreachable!("Should never happen because `*self` must be `Owned` variant"),
+        };
+        s.reserve(additional);
+        s
+    }
+}
+
+impl<'a, T, P> SharedCow<'a, [T], P>
+where
+    T: Clone,
+    P: Deref<Target = [T]>,
+{
+    /// Returns mutable reference to the `Owned(_)` vector with at least
+    /// `additional` elements of spare capacity.
+    ///
+    /// This clones the value if necessary, allocating the whole capacity at
+    /// once.
+    pub(crate) fn reserve_owned(&mut self, additional: usize) -> &mut Vec<T> {
+        if !self.is_owned() {
+            let mut v = Vec::with_capacity(self.len() + additional);
+            v.extend_from_slice(self);
+            *self = SharedCow::Owned(v);
+        }
+        let v = match *self {
+            SharedCow::Owned(ref mut v) => v,
+            _ => unreachable!("Should never happen because `*self` must be `Owned` variant"),
+        };
+        v.reserve(additional);
+        v
+    }
+}
+
+impl_str_add! { &'a str }
+impl_str_add! { &'a String }
+impl_str_add! { String }
+impl_str_add! { char }
+impl_str_add! { std::borrow::Cow<'a, str> }
+impl_str_add! { SharedCow<'a, str, P> }
+
+impl<'a, P> std::ops::AddAssign<&'a str> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: &'a str) {
+        if !self.is_owned() && self.is_empty() {
+            *self = SharedCow::Borrowed(rhs)
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(rhs);
+        }
+    }
+}
+
+impl<'a, P> std::ops::AddAssign<&'a String> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: &'a String) {
+        *self += rhs.as_str();
+    }
+}
+
+impl<'a, P> std::ops::AddAssign<String> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: String) {
+        if !self.is_owned() && self.is_empty() {
+            *self = SharedCow::Owned(rhs)
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
+
+impl<'a, P> std::ops::AddAssign<char> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: char) {
+        self.reserve_owned(rhs.len_utf8()).push(rhs);
+    }
+}
+
+impl<'a, P> std::ops::AddAssign<std::borrow::Cow<'a, str>> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: std::borrow::Cow<'a, str>) {
+        if !self.is_owned() && self.is_empty() {
+            *self = rhs.into()
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
+
+impl<'a, P> std::ops::AddAssign<SharedCow<'a, str, P>> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn add_assign(&mut self, rhs: SharedCow<'a, str, P>) {
+        if !self.is_owned() && self.is_empty() {
+            *self = rhs;
+        } else if !rhs.is_empty() {
+            self.reserve_owned(rhs.len()).push_str(&rhs);
+        }
+    }
+}
+
+impl<'a, P> Extend<char> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        if lower != 0 {
+            self.reserve_owned(lower);
+        }
+        for c in iter {
+            *self += c;
+        }
+    }
+}
+
+impl<'a, 'b, P> Extend<&'b str> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn extend<I: IntoIterator<Item = &'b str>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter();
+        // Empty strings keep the value untouched.
+        let first = match iter.find(|s| !s.is_empty()) {
+            Some(first) => first,
+            None => return,
+        };
+        let (lower, _) = iter.size_hint();
+        self.reserve_owned(first.len() + lower).push_str(first);
+        for s in iter {
+            if !s.is_empty() {
+                self.reserve_owned(s.len()).push_str(s);
+            }
+        }
+    }
+}
+
+impl<'a, P> Extend<String> for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        for s in iter {
+            *self += s;
+        }
+    }
+}
+
+/// Writing an empty string keeps the value untouched.
+impl<'a, P> std::fmt::Write for SharedCow<'a, str, P>
+where
+    P: Deref<Target = str>,
+{
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if !s.is_empty() {
+            self.reserve_owned(s.len()).push_str(s);
+        }
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> std::fmt::Result {
+        *self += c;
+        Ok(())
+    }
+}
+
+/// Writing an empty buffer keeps the value untouched.
+impl<'a, P> std::io::Write for SharedCow<'a, [u8], P>
+where
+    P: Deref<Target = [u8]>,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !buf.is_empty() {
+            self.reserve_owned(buf.len()).extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b, B, C, P, Q> PartialEq<SharedCow<'b, C, Q>> for SharedCow<'a, B, P>
+where
+    B: ?Sized + PartialEq<C> + ToOwned,
+    C: ?Sized + ToOwned,
+    P: Deref<Target = B>,
+    Q: Deref<Target = C>,
+{
+    #[inline]
+    fn eq(&self, other: &SharedCow<'b, C, Q>) -> bool {
+        **self == **other
+    }
+}
+
+impl_eq_slice! { SharedCow<'a, [A], P>, &'b [B], Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, &'b mut [B], Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, Vec<B>, Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, &'b Vec<B>, Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, Box<[B]>, Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, std::rc::Rc<[B]>, Clone }
+impl_eq_slice! { SharedCow<'a, [A], P>, std::sync::Arc<[B]>, Clone }
+
+impl<'a, 'b, A, B, P> PartialEq<std::borrow::Cow<'b, [B]>> for SharedCow<'a, [A], P>
+where
+    A: Clone + ToOwned + PartialEq<B>,
+    B: Clone + ToOwned,
+    P: Deref<Target = [A]>,
+{
+    #[inline]
+    fn eq(&self, other: &std::borrow::Cow<'b, [B]>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, B, P> Eq for SharedCow<'a, B, P>
+where
+    B: ?Sized + Eq + ToOwned,
+    P: Deref<Target = B>,
+{
+}
+
+impl<'a, 'b, B, C, P, Q> PartialOrd<SharedCow<'b, C, Q>> for SharedCow<'a, B, P>
+where
+    B: ?Sized + PartialOrd<C> + ToOwned,
+    C: ?Sized + ToOwned,
+    P: Deref<Target = B>,
+    Q: Deref<Target = C>,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &SharedCow<'b, C, Q>) -> Option<std::cmp::Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<'a, B, P> Ord for SharedCow<'a, B, P>
+where
+    B: ?Sized + Ord + ToOwned,
+    P: Deref<Target = B>,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
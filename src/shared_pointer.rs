@@ -0,0 +1,124 @@
+//! `SharedPointer`.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Shared pointer which can be used as the `Shared` variant of [`SharedCow`].
+///
+/// This is implemented for [`Rc`] and [`Arc`] of sized `Clone` types, `str`,
+/// `Path`, `OsStr`, `CStr` and `[T]`. It is not implemented for pointers of
+/// other unsized types, even if they have `From<&B>` and `From<B::Owned>`
+/// conversions.
+///
+/// [`SharedCow`]: `crate::SharedCow`
+pub trait SharedPointer<B>: Clone + std::ops::Deref<Target = B>
+where
+    B: ToOwned + ?Sized,
+{
+    /// Creates a new shared pointer from the borrowed data.
+    ///
+    /// This always clones the value.
+    fn from_borrowed(borrowed: &B) -> Self;
+
+    /// Creates a new shared pointer from the owned data.
+    fn from_owned(owned: <B as ToOwned>::Owned) -> Self;
+
+    /// Returns a mutable reference to the data if the pointer is unique.
+    fn get_mut(this: &mut Self) -> Option<&mut B>;
+
+    /// Moves the data out of the pointer if it is the only strong pointer to
+    /// the data, or returns the pointer as is otherwise.
+    ///
+    /// This lets [`SharedCow`] reuse uniquely held data without cloning.
+    /// The default implementation always returns `Err(this)`, as the data
+    /// in pointers of unsized types (such as `Rc<str>`) cannot be moved into
+    /// `<B as ToOwned>::Owned`.
+    ///
+    /// [`SharedCow`]: `crate::SharedCow`
+    fn try_unwrap(this: Self) -> Result<<B as ToOwned>::Owned, Self> {
+        Err(this)
+    }
+
+    /// Returns the number of strong pointers to the data.
+    ///
+    /// This should return 0 if the data is not reference counted, so that
+    /// [`SharedCow::strong_count`] returns `None` for it.
+    ///
+    /// [`SharedCow::strong_count`]: `crate::SharedCow::strong_count`
+    fn strong_count(this: &Self) -> usize;
+
+    /// Returns the number of weak pointers to the data.
+    fn weak_count(this: &Self) -> usize;
+}
+
+/// Sized data is put into the pointer by [`Rc::new`].
+impl<T> SharedPointer<T> for Rc<T>
+where
+    T: Clone,
+{
+    fn from_borrowed(borrowed: &T) -> Self {
+        Rc::new(borrowed.clone())
+    }
+
+    fn from_owned(owned: T) -> Self {
+        Rc::new(owned)
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Rc::get_mut(this)
+    }
+
+    fn try_unwrap(this: Self) -> Result<T, Self> {
+        Rc::try_unwrap(this)
+    }
+
+    fn strong_count(this: &Self) -> usize {
+        Rc::strong_count(this)
+    }
+
+    fn weak_count(this: &Self) -> usize {
+        Rc::weak_count(this)
+    }
+}
+
+/// Sized data is put into the pointer by [`Arc::new`].
+impl<T> SharedPointer<T> for Arc<T>
+where
+    T: Clone,
+{
+    fn from_borrowed(borrowed: &T) -> Self {
+        Arc::new(borrowed.clone())
+    }
+
+    fn from_owned(owned: T) -> Self {
+        Arc::new(owned)
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Arc::get_mut(this)
+    }
+
+    fn try_unwrap(this: Self) -> Result<T, Self> {
+        Arc::try_unwrap(this)
+    }
+
+    fn strong_count(this: &Self) -> usize {
+        Arc::strong_count(this)
+    }
+
+    fn weak_count(this: &Self) -> usize {
+        Arc::weak_count(this)
+    }
+}
+
+impl_shared_pointer! { Rc, str }
+impl_shared_pointer! { Rc, std::path::Path }
+impl_shared_pointer! { Rc, std::ffi::OsStr }
+impl_shared_pointer! { Rc, std::ffi::CStr }
+impl_shared_pointer! { Rc, [T], T }
+
+impl_shared_pointer! { Arc, str }
+impl_shared_pointer! { Arc, std::path::Path }
+impl_shared_pointer! { Arc, std::ffi::OsStr }
+impl_shared_pointer! { Arc, std::ffi::CStr }
+impl_shared_pointer! { Arc, [T], T }
@@ -0,0 +1,219 @@
+//! `Sliced`.
+
+use std::ops::{Bound, Deref, Index, IndexMut, RangeBounds};
+
+use crate::{SharedCow, SharedPointer};
+
+/// Range type used to slice the data.
+type SliceRange = (Bound<usize>, Bound<usize>);
+
+/// Shared pointer to a subslice of the data shared by `P`.
+///
+/// This keeps the whole shared data alive and points to the range in it, so
+/// slicing the `Shared(_)` variant never clones the data.
+#[derive(Clone)]
+pub struct Sliced<P> {
+    /// Shared pointer to the whole data.
+    shared: P,
+    /// Start offset in the whole data.
+    start: usize,
+    /// End offset in the whole data, or `None` for the end of the data.
+    end: Option<usize>,
+}
+
+impl<P> Sliced<P> {
+    /// Creates a new pointer to the whole data.
+    pub fn new(shared: P) -> Self {
+        Sliced {
+            shared,
+            start: 0,
+            end: None,
+        }
+    }
+
+    /// Returns the range in the whole data.
+    fn range(&self) -> SliceRange {
+        (
+            Bound::Included(self.start),
+            self.end.map_or(Bound::Unbounded, Bound::Excluded),
+        )
+    }
+
+    /// Returns the subslice of the current range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or invalid for the data.
+    fn slice<B>(&self, range: SliceRange) -> Self
+    where
+        B: ?Sized + Index<SliceRange, Output = B>,
+        P: Clone + Deref<Target = B>,
+    {
+        // Validate the range before composing offsets.
+        let _ = &(**self)[range];
+        let start = match range.0 {
+            Bound::Included(start) => self.start + start,
+            Bound::Excluded(start) => self.start + start + 1,
+            Bound::Unbounded => self.start,
+        };
+        let end = match range.1 {
+            Bound::Included(end) => Some(self.start + end + 1),
+            Bound::Excluded(end) => Some(self.start + end),
+            Bound::Unbounded => self.end,
+        };
+        Sliced {
+            shared: self.shared.clone(),
+            start,
+            end,
+        }
+    }
+}
+
+impl<B, P> Deref for Sliced<P>
+where
+    B: ?Sized + Index<SliceRange, Output = B>,
+    P: Deref<Target = B>,
+{
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        &self.shared[self.range()]
+    }
+}
+
+impl<B, P> SharedPointer<B> for Sliced<P>
+where
+    B: ToOwned + ?Sized + Index<SliceRange, Output = B> + IndexMut<SliceRange>,
+    P: SharedPointer<B>,
+{
+    fn from_borrowed(borrowed: &B) -> Self {
+        Sliced::new(P::from_borrowed(borrowed))
+    }
+
+    fn from_owned(owned: <B as ToOwned>::Owned) -> Self {
+        Sliced::new(P::from_owned(owned))
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut B> {
+        let range = this.range();
+        P::get_mut(&mut this.shared).map(|b| &mut b[range])
+    }
+
+    fn strong_count(this: &Self) -> usize {
+        P::strong_count(&this.shared)
+    }
+
+    fn weak_count(this: &Self) -> usize {
+        P::weak_count(&this.shared)
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    /// Converts the value into a value which can be sliced without cloning.
+    #[must_use]
+    pub fn into_sliced(self) -> SharedCow<'a, B, Sliced<P>> {
+        match self {
+            SharedCow::Borrowed(b) => SharedCow::Borrowed(b),
+            SharedCow::Owned(o) => SharedCow::Owned(o),
+            SharedCow::Shared(s) => SharedCow::Shared(Sliced::new(s)),
+        }
+    }
+}
+
+impl<'a, B, P> From<SharedCow<'a, B, P>> for SharedCow<'a, B, Sliced<P>>
+where
+    B: 'a + ToOwned + ?Sized,
+{
+    fn from(cow: SharedCow<'a, B, P>) -> Self {
+        cow.into_sliced()
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, Sliced<P>>
+where
+    B: 'a + ToOwned + ?Sized + Index<SliceRange, Output = B>,
+    P: Clone + Deref<Target = B>,
+{
+    /// Returns a subslice of the value.
+    ///
+    /// `Borrowed(_)` and `Shared(_)` values are sliced without cloning the
+    /// data, and `Owned(_)` value is sliced into a new `Owned(_)` value.
+    /// Call [`share`][`SharedCow::share`] beforehand to avoid clones for
+    /// `Owned(_)` value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or invalid for the data.
+    #[must_use]
+    pub fn slice<R>(&self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        use std::borrow::Borrow;
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        match self {
+            SharedCow::Borrowed(b) => SharedCow::Borrowed(&b[range]),
+            SharedCow::Owned(o) => SharedCow::Owned(o.borrow()[range].to_owned()),
+            SharedCow::Shared(s) => SharedCow::Shared(s.slice(range)),
+        }
+    }
+}
+
+impl<'a, P> SharedCow<'a, str, Sliced<P>>
+where
+    P: Clone + Deref<Target = str>,
+{
+    /// Returns a subslice at the position of `sub`, which points into `self`.
+    fn slice_ref(&self, sub: &str) -> Self {
+        let start = sub.as_ptr() as usize - self.as_ptr() as usize;
+        self.slice(start..start + sub.len())
+    }
+
+    /// Returns an iterator of subslices separated by `sep`.
+    ///
+    /// See [`str::split`].
+    pub fn split<'s>(&'s self, sep: &'s str) -> impl Iterator<Item = Self> + 's {
+        (**self).split(sep).map(move |sub| self.slice_ref(sub))
+    }
+
+    /// Returns an iterator of lines as subslices.
+    ///
+    /// See [`str::lines`].
+    pub fn lines(&self) -> impl Iterator<Item = Self> + '_ {
+        (**self).lines().map(move |sub| self.slice_ref(sub))
+    }
+
+    /// Returns an iterator of subslices separated by whitespace.
+    ///
+    /// See [`str::split_whitespace`].
+    pub fn split_whitespace(&self) -> impl Iterator<Item = Self> + '_ {
+        (**self)
+            .split_whitespace()
+            .map(move |sub| self.slice_ref(sub))
+    }
+}
+
+impl<'a, T, P> SharedCow<'a, [T], Sliced<P>>
+where
+    T: Clone,
+    P: Clone + Deref<Target = [T]>,
+{
+    /// Returns an iterator of subslices with `size` elements.
+    ///
+    /// The last subslice may be shorter than `size`.
+    /// See [`slice::chunks`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = Self> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+        let len = self.len();
+        (0..len)
+            .step_by(size)
+            .map(move |start| self.slice(start..std::cmp::min(start + size, len)))
+    }
+}
@@ -0,0 +1,150 @@
+//! Fallible allocation.
+
+use std::collections::TryReserveError;
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use crate::shared_cow::replace_with;
+use crate::{SharedCow, SharedPointer};
+
+/// [`ToOwned`] with fallible allocation.
+///
+/// This is implemented for `str`, `[T]`, `OsStr`, `Path` and `CStr`.
+/// Other types (including sized `Clone` types) can implement this to use
+/// fallible methods of [`SharedCow`].
+pub trait TryToOwned: ToOwned {
+    /// Creates owned data from borrowed data, returning an error if the
+    /// allocation fails.
+    fn try_to_owned(&self) -> Result<<Self as ToOwned>::Owned, TryReserveError>;
+}
+
+impl TryToOwned for str {
+    fn try_to_owned(&self) -> Result<String, TryReserveError> {
+        let mut owned = String::new();
+        owned.try_reserve_exact(self.len())?;
+        owned.push_str(self);
+        Ok(owned)
+    }
+}
+
+impl<T: Clone> TryToOwned for [T] {
+    fn try_to_owned(&self) -> Result<Vec<T>, TryReserveError> {
+        let mut owned = Vec::new();
+        owned.try_reserve_exact(self.len())?;
+        owned.extend_from_slice(self);
+        Ok(owned)
+    }
+}
+
+impl TryToOwned for OsStr {
+    fn try_to_owned(&self) -> Result<OsString, TryReserveError> {
+        let mut owned = OsString::new();
+        owned.try_reserve_exact(self.len())?;
+        owned.push(self);
+        Ok(owned)
+    }
+}
+
+impl TryToOwned for Path {
+    fn try_to_owned(&self) -> Result<PathBuf, TryReserveError> {
+        self.as_os_str().try_to_owned().map(PathBuf::from)
+    }
+}
+
+impl TryToOwned for CStr {
+    fn try_to_owned(&self) -> Result<CString, TryReserveError> {
+        let owned = self.to_bytes_with_nul().try_to_owned()?;
+        Ok(CString::from_vec_with_nul(owned).expect("should never fail: copied from `CStr`"))
+    }
+}
+
+impl<'a, B, P> SharedCow<'a, B, P>
+where
+    B: 'a + TryToOwned + ?Sized,
+    P: SharedPointer<B>,
+{
+    /// Creates a new owned value, returning an error if the allocation
+    /// fails.
+    ///
+    /// This behaves like [`into_owned`][`SharedCow::into_owned`], and returns
+    /// the original value together with the error on failure.
+    /// Uniquely held `Shared(_)` data is moved out of the pointer as
+    /// `into_owned` does, without allocation.
+    pub fn try_into_owned(self) -> Result<<B as ToOwned>::Owned, (Self, TryReserveError)> {
+        match self.unwrap_shared() {
+            SharedCow::Owned(owned) => Ok(owned),
+            cow => match (*cow).try_to_owned() {
+                Ok(owned) => Ok(owned),
+                Err(e) => Err((cow, e)),
+            },
+        }
+    }
+
+    /// Returns mutable reference to the `Owned(_)` value, returning an error
+    /// if the allocation fails.
+    ///
+    /// This behaves like [`to_mut`][`SharedCow::to_mut`], and leaves the
+    /// value unchanged on failure.
+    /// Uniquely held `Shared(_)` data is moved out of the pointer as `to_mut`
+    /// does, without allocation.
+    pub fn try_to_mut(&mut self) -> Result<&mut <B as ToOwned>::Owned, TryReserveError> {
+        if self.is_shared() {
+            replace_with(self, SharedCow::unwrap_shared);
+        }
+        if !self.is_owned() {
+            *self = SharedCow::Owned((**self).try_to_owned()?);
+        }
+        match *self {
+            SharedCow::Owned(ref mut owned) => Ok(owned),
+            _ => unreachable!("Should never happen because `*self` must be `Owned` variant"),
+        }
+    }
+
+    /// Creates a new shared value, returning an error if the allocation
+    /// fails.
+    ///
+    /// This behaves like [`into_shared`][`SharedCow::into_shared`], and
+    /// returns the original value together with the error on failure.
+    ///
+    /// The shared pointers have no fallible constructors, so this checks that
+    /// the memory for the new pointer can be allocated before creating it,
+    /// by allocating and freeing a buffer of the same size.
+    /// This doubles the allocation cost of `Borrowed(_)` and `Owned(_)`
+    /// values, which may be significant for large data.
+    /// The process may still abort if the memory is exhausted in the
+    /// meantime, for example by other threads.
+    pub fn try_into_shared(self) -> Result<P, (Self, TryReserveError)> {
+        if let SharedCow::Shared(shared) = self {
+            return Ok(shared);
+        }
+        if let Err(e) = try_reserve_shared::<B>(&self) {
+            return Err((self, e));
+        }
+        Ok(self.into_shared())
+    }
+
+    /// Turns `self` into `Borrowed` or `Shared` variant, returning an error
+    /// if the allocation fails.
+    ///
+    /// This behaves like [`share`][`SharedCow::share`], and leaves the value
+    /// unchanged on failure.
+    /// `Owned(_)` data is checked as
+    /// [`try_into_shared`][`SharedCow::try_into_shared`] does, with the same
+    /// extra allocation and limitation.
+    pub fn try_share(&mut self) -> Result<&mut Self, TryReserveError> {
+        if self.is_owned() {
+            try_reserve_shared::<B>(self)?;
+        }
+        Ok(self.share())
+    }
+}
+
+/// Checks that the memory for a new shared pointer to `data` can be
+/// allocated, by allocating and freeing a buffer of that size.
+///
+/// The size is that of the data and the two reference counters of
+/// [`Rc`][`std::rc::Rc`] and [`Arc`][`std::sync::Arc`].
+fn try_reserve_shared<B: ?Sized>(data: &B) -> Result<(), TryReserveError> {
+    let size = std::mem::size_of_val(data).saturating_add(2 * std::mem::size_of::<usize>());
+    Vec::<u8>::new().try_reserve_exact(size)
+}
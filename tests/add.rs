@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+/// Returns an empty `Owned(_)` value with spare capacity.
+fn empty_owned<'a>() -> ArcCow<'a, str> {
+    ArcCow::Owned(String::with_capacity(64))
+}
+
+/// Asserts that `cow` is still the buffer of `empty_owned()`.
+fn assert_buffer_kept(cow: &ArcCow<'_, str>, ptr: *const u8) {
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+}
+
+#[test]
+fn add_assign_to_empty_owned_keeps_buffer() {
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += "hello";
+    assert_buffer_kept(&cow, ptr);
+    assert_eq!(&*cow, "hello");
+
+    let hello = String::from("hello");
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += &hello;
+    assert_buffer_kept(&cow, ptr);
+
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += String::from("hello");
+    assert_buffer_kept(&cow, ptr);
+
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += Cow::Borrowed("hello");
+    assert_buffer_kept(&cow, ptr);
+
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += ArcCow::Borrowed("hello");
+    assert_buffer_kept(&cow, ptr);
+
+    let mut cow = empty_owned();
+    let ptr = cow.as_ptr();
+    cow += Arc::<str>::from("hello");
+    assert_buffer_kept(&cow, ptr);
+    assert_eq!(&*cow, "hello");
+
+    let mut cow = RcCow::<str>::Owned(String::with_capacity(64));
+    let ptr = cow.as_ptr();
+    cow += Rc::<str>::from("hello");
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+}
+
+#[test]
+fn add_assign_to_empty_borrowed_takes_rhs() {
+    let mut cow = ArcCow::Borrowed("");
+    cow += "hello";
+    assert!(cow.is_borrowed());
+
+    let mut cow = ArcCow::Borrowed("");
+    cow += Arc::<str>::from("hello");
+    assert!(cow.is_shared());
+
+    let mut cow = ArcCow::Borrowed("");
+    cow += String::from("hello");
+    assert!(cow.is_owned());
+}
+
+#[test]
+fn extend_reserves_lower_bound() {
+    let mut cow = ArcCow::Borrowed("abc");
+    cow.extend(std::iter::repeat('x').take(100));
+    assert_eq!(cow.len(), 103);
+    match cow {
+        ArcCow::Owned(ref s) => assert!(s.capacity() >= 103),
+        _ => panic!("should be owned"),
+    }
+
+    let mut cow = ArcCow::Borrowed("abc");
+    cow.extend(vec!["x"; 100]);
+    assert_eq!(cow.len(), 103);
+}
+
+#[test]
+fn extend_with_empty_strings_keeps_value() {
+    let data = "abc";
+    let mut cow = ArcCow::Borrowed(data);
+    cow.extend(vec![""; 3]);
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let shared = Arc::<str>::from("abc");
+    let mut cow = ArcCow::Shared(shared.clone());
+    cow.extend(Vec::<&str>::new());
+    assert!(cow.is_shared());
+    assert!(Arc::ptr_eq(cow.as_shared().unwrap(), &shared));
+
+    cow.extend(vec!["", "de", "", "f"]);
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "abcdef");
+}
+
+#[test]
+fn add_other_family() {
+    let cow = ArcCow::Borrowed("ab") + Rc::<str>::from("cd");
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "abcd");
+
+    let cow = RcCow::Borrowed("ab") + Arc::<str>::from("cd");
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "abcd");
+
+    let mut cow = RcCow::Borrowed("ab");
+    cow += Arc::<str>::from("");
+    assert!(cow.is_borrowed());
+    cow += Arc::<str>::from("cd");
+    assert_eq!(&*cow, "abcd");
+
+    let mut cow = ArcCow::Borrowed("");
+    cow += Rc::<str>::from("cd");
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "cd");
+}
@@ -0,0 +1,55 @@
+//! Global allocator counting the allocations and limiting their sizes.
+//!
+//! The counter and the limit are per thread, so that tests running in
+//! parallel do not interfere with each other.
+#![allow(dead_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIMIT: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+struct TestAllocator;
+
+unsafe impl GlobalAlloc for TestAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > LIMIT.with(Cell::get) {
+            return std::ptr::null_mut();
+        }
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > LIMIT.with(Cell::get) {
+            return std::ptr::null_mut();
+        }
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TestAllocator = TestAllocator;
+
+/// Returns the result of `f` and the number of allocations it made.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Calls `f`, failing the allocations larger than `limit` bytes.
+pub fn with_allocation_limit<R>(limit: usize, f: impl FnOnce() -> R) -> R {
+    let old = LIMIT.with(|l| l.replace(limit));
+    let result = f();
+    LIMIT.with(|l| l.set(old));
+    result
+}
@@ -0,0 +1,92 @@
+use std::ffi::{CStr, OsStr};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+const INVALID: &[u8] = b"a\xffb";
+
+macro_rules! convert_tests {
+    ($name:ident, $cow:ident, $rc:ident) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            fn from_utf8_valid() {
+                let bytes = b"hello".to_vec();
+
+                let s = $cow::<str>::from_utf8($cow::Borrowed(&bytes[..])).unwrap();
+                assert!(s.is_borrowed());
+                assert_eq!(s.as_ptr(), bytes.as_ptr());
+
+                let owned = bytes.clone();
+                let ptr = owned.as_ptr();
+                let s = $cow::<str>::from_utf8($cow::Owned(owned)).unwrap();
+                assert!(s.is_owned());
+                assert_eq!(s.as_ptr(), ptr);
+
+                let shared = $rc::<[u8]>::from(&bytes[..]);
+                let s = $cow::<str>::from_utf8($cow::Shared(shared.clone())).unwrap();
+                assert!(s.is_shared());
+                assert_eq!(s.as_ptr(), shared.as_ptr());
+                assert_eq!(&*s, "hello");
+            }
+
+            #[test]
+            fn from_utf8_invalid() {
+                let cow = $cow::<str>::from_utf8($cow::Borrowed(INVALID)).unwrap_err();
+                assert!(cow.is_borrowed());
+                assert_eq!(cow.as_ptr(), INVALID.as_ptr());
+
+                let owned = INVALID.to_vec();
+                let ptr = owned.as_ptr();
+                let cow = $cow::<str>::from_utf8($cow::Owned(owned)).unwrap_err();
+                assert!(cow.is_owned());
+                assert_eq!(cow.as_ptr(), ptr);
+
+                let shared = $rc::<[u8]>::from(INVALID);
+                let cow = $cow::<str>::from_utf8($cow::Shared(shared.clone())).unwrap_err();
+                assert!(cow.is_shared());
+                assert_eq!(cow.as_ptr(), shared.as_ptr());
+            }
+
+            #[test]
+            fn shared_into_string() {
+                let cow = $cow::Shared($rc::<OsStr>::from(OsStr::new("os")));
+                assert_eq!(&*cow.into_string().unwrap(), "os");
+                let cow = $cow::Shared($rc::<Path>::from(Path::new("/tmp")));
+                assert_eq!(&*cow.into_string().unwrap(), "/tmp");
+                let c = CStr::from_bytes_with_nul(b"c\0").unwrap();
+                let cow = $cow::Shared($rc::<CStr>::from(c));
+                assert_eq!(&*cow.into_string().unwrap(), "c");
+
+                let c = CStr::from_bytes_with_nul(b"\xff\0").unwrap();
+                let shared = $rc::<CStr>::from(c);
+                let cow = $cow::Shared(shared.clone()).into_string().unwrap_err();
+                assert!(cow.is_shared());
+                assert!(std::ptr::eq::<CStr>(&*cow, &*shared));
+            }
+
+            #[cfg(unix)]
+            #[test]
+            fn shared_into_string_invalid() {
+                use std::os::unix::ffi::OsStrExt;
+
+                let os_str = OsStr::from_bytes(INVALID);
+                let shared = $rc::<OsStr>::from(os_str);
+                let cow = $cow::Shared(shared.clone()).into_string().unwrap_err();
+                assert!(cow.is_shared());
+                assert!(std::ptr::eq::<OsStr>(&*cow, &*shared));
+
+                let shared = $rc::<Path>::from(Path::new(os_str));
+                let cow = $cow::Shared(shared.clone()).into_string().unwrap_err();
+                assert!(cow.is_shared());
+                assert!(std::ptr::eq::<Path>(&*cow, &*shared));
+            }
+        }
+    };
+}
+
+convert_tests! { arc_cow, ArcCow, Arc }
+convert_tests! { rc_cow, RcCow, Rc }
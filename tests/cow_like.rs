@@ -0,0 +1,71 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, CowLike, RcCow, SharedPointer};
+
+mod common;
+
+use crate::common::count_allocations;
+
+fn to_shared<'a, C>(cow: C) -> C::Shared
+where
+    C: CowLike<'a, [u8]>,
+    C::Shared: SharedPointer<[u8]>,
+{
+    cow.into_shared()
+}
+
+fn to_rc<'a, C: CowLike<'a, [u8]>>(cow: C) -> Rc<[u8]> {
+    cow.into_rccow().into_shared()
+}
+
+#[test]
+fn into_shared() {
+    let data = vec![1u8, 2, 3];
+
+    let arc: Arc<[u8]> = to_shared(Cow::Borrowed(&data[..]));
+    assert_eq!(*arc, *data);
+    let arc: Arc<[u8]> = to_shared(Cow::<[u8]>::Owned(data.clone()));
+    assert_eq!(*arc, *data);
+    let arc: Arc<[u8]> = to_shared(ArcCow::Borrowed(&data[..]));
+    assert_eq!(*arc, *data);
+    let rc: Rc<[u8]> = to_shared(RcCow::<[u8]>::Owned(data.clone()));
+    assert_eq!(*rc, *data);
+
+    let shared = Rc::<[u8]>::from(&data[..]);
+    let (rc, count) = count_allocations(|| to_shared(RcCow::Shared(shared.clone())));
+    assert_eq!(count, 0);
+    assert!(Rc::ptr_eq(&rc, &shared));
+    let shared = Arc::<[u8]>::from(&data[..]);
+    let arc = to_shared(ArcCow::Shared(shared.clone()));
+    assert!(Arc::ptr_eq(&arc, &shared));
+}
+
+#[test]
+fn into_other_family() {
+    let data = vec![1u8, 2, 3];
+
+    assert_eq!(*to_rc(Cow::Borrowed(&data[..])), *data);
+    assert_eq!(*to_rc(ArcCow::Shared(Arc::from(&data[..]))), *data);
+    let shared = Rc::<[u8]>::from(&data[..]);
+    assert!(Rc::ptr_eq(&to_rc(RcCow::Shared(shared.clone())), &shared));
+}
+
+#[test]
+fn into_shared_moves_unique_data() {
+    let data = vec![1u8, 2, 3];
+    let ptr = data.as_ptr();
+
+    // Only the new `Arc` is allocated, and the vector is moved into it.
+    let cow = Cow::<Vec<u8>>::Owned(data);
+    let (arc, count) = count_allocations(|| cow.into_shared());
+    assert_eq!(count, 1);
+    assert_eq!(arc.as_ptr(), ptr);
+
+    let cow = ArcCow::Shared(arc);
+    let (rc, count) = count_allocations(|| cow.into_rccow().into_shared());
+    assert_eq!(count, 1);
+    assert_eq!(rc.deref().as_ptr(), ptr);
+}
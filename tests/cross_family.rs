@@ -0,0 +1,174 @@
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+mod common;
+
+use crate::common::count_allocations;
+
+#[test]
+fn compare_arccow_and_rccow() {
+    let arc = ArcCow::Shared(Arc::<str>::from("a"));
+    let rc = RcCow::<str>::Owned("a".to_owned());
+    assert_eq!(arc, rc);
+    assert_eq!(rc, arc);
+    assert!(RcCow::Borrowed("b") > arc);
+    assert!(arc < RcCow::Borrowed("b"));
+    assert_ne!(arc, RcCow::Borrowed("b"));
+}
+
+#[test]
+fn compare_with_pointers() {
+    let cow = ArcCow::Borrowed("b");
+    let (boxed, rc, arc) = (
+        Box::<str>::from("b"),
+        Rc::<str>::from("b"),
+        Arc::<str>::from("b"),
+    );
+    assert!(cow == boxed);
+    assert!(boxed == cow);
+    assert!(cow == rc);
+    assert!(rc == cow);
+    assert!(cow == arc);
+    assert!(arc == cow);
+    let (boxed, rc, arc) = (
+        Box::<str>::from("a"),
+        Rc::<str>::from("c"),
+        Arc::<str>::from("c"),
+    );
+    assert!(cow > boxed);
+    assert!(boxed < cow);
+    assert!(cow < rc);
+    assert!(rc > cow);
+    assert!(cow < arc);
+    assert!(arc > cow);
+
+    let cow = RcCow::<Path>::Borrowed(Path::new("/tmp"));
+    let boxed = Box::<Path>::from(Path::new("/tmp"));
+    let arc = Arc::<Path>::from(Path::new("/tmp"));
+    assert!(cow == boxed);
+    assert!(cow == arc);
+
+    let cow = RcCow::<[u8]>::Owned(vec![1, 2]);
+    let boxed = Box::<[u8]>::from(&[1, 2][..]);
+    let rc = Rc::<[u8]>::from(&[1, 2][..]);
+    let arc = Arc::<[u8]>::from(&[1, 2][..]);
+    assert!(cow == boxed);
+    assert!(cow == rc);
+    assert!(cow == arc);
+    let arc = Arc::<[u8]>::from(&[1][..]);
+    assert!(cow != arc);
+}
+
+#[test]
+fn compare_path_and_os_str_with_strings() {
+    let (string, os_string) = ("b".to_owned(), OsString::from("b"));
+
+    let path = ArcCow::Borrowed(Path::new("b"));
+    assert!(path == *"b");
+    assert!(*"b" == path);
+    assert!(path == "b");
+    assert!("b" == path);
+    assert!(path == string);
+    assert!(string == path);
+    assert!(path == *OsStr::new("b"));
+    assert!(*OsStr::new("b") == path);
+    assert!(path == OsStr::new("b"));
+    assert!(OsStr::new("b") == path);
+    assert!(path == os_string);
+    assert!(os_string == path);
+    assert!(path > "a");
+    assert!("a" < path);
+    let c = OsString::from("c");
+    assert!(path < c);
+    assert!(c > path);
+
+    let os_str = RcCow::Borrowed(OsStr::new("b"));
+    assert!(os_str == *"b");
+    assert!(*"b" == os_str);
+    assert!(os_str == "b");
+    assert!("b" == os_str);
+    assert!(os_str == string);
+    assert!(string == os_str);
+    assert!(os_str < "c");
+    assert!("c" > os_str);
+    assert!(os_str != "a");
+}
+
+#[test]
+fn from_box() {
+    let boxed = Box::<str>::from("hello");
+    let ptr = boxed.as_ptr();
+    let (cow, count) = count_allocations(|| ArcCow::<str>::from(boxed));
+    assert_eq!(count, 0);
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let boxed = Box::<[u8]>::from(&[1, 2][..]);
+    let ptr = boxed.as_ptr();
+    let cow = RcCow::<[u8]>::from(boxed);
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+}
+
+#[test]
+fn into_box() {
+    let cow = ArcCow::<str>::Owned("hello".to_owned());
+    assert_eq!(&*Box::<str>::from(cow), "hello");
+    let cow = RcCow::Shared(Rc::<[u8]>::from(&[1, 2][..]));
+    assert_eq!(*Box::<[u8]>::from(cow), [1, 2]);
+    let cow = ArcCow::Borrowed(Path::new("/tmp"));
+    assert_eq!(&*Box::<Path>::from(cow), Path::new("/tmp"));
+}
+
+#[test]
+fn pointer_into_other_family_cow() {
+    // The unique data is moved, and only the new pointer is allocated.
+    let rc = Rc::new(vec![1u8, 2, 3]);
+    let ptr = rc.as_ptr();
+    let (cow, count) = count_allocations(|| ArcCow::from(rc));
+    assert_eq!(count, 1);
+    assert!(cow.is_shared());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let arc = Arc::new(vec![1u8, 2, 3]);
+    let ptr = arc.as_ptr();
+    let cow = RcCow::from(arc);
+    assert_eq!(cow.as_ptr(), ptr);
+
+    // Shared or unsized data is cloned.
+    let rc = Rc::new(vec![1u8, 2, 3]);
+    let cow = ArcCow::from(rc.clone());
+    assert_ne!(cow.as_ptr(), rc.as_ptr());
+    let arc = Arc::<str>::from("hello");
+    let cow = RcCow::from(arc.clone());
+    assert_eq!(&*cow, "hello");
+    assert_ne!(cow.as_ptr(), arc.as_ptr());
+}
+
+#[test]
+fn cow_into_pointer() {
+    // The same family shares the pointer.
+    let arc = Arc::<str>::from("hello");
+    let shared = Arc::<str>::from(ArcCow::Shared(arc.clone()));
+    assert!(Arc::ptr_eq(&shared, &arc));
+    let rc = Rc::<str>::from("hello");
+    let shared = Rc::<str>::from(RcCow::Shared(rc.clone()));
+    assert!(Rc::ptr_eq(&shared, &rc));
+
+    // The other family moves the unique data.
+    let cow = RcCow::Shared(Rc::new(vec![1u8, 2, 3]));
+    let ptr = cow.as_ptr();
+    let (arc, count) = count_allocations(|| Arc::<Vec<u8>>::from(cow));
+    assert_eq!(count, 1);
+    assert_eq!(arc.as_ptr(), ptr);
+    let rc = Rc::<Vec<u8>>::from(ArcCow::Shared(arc));
+    assert_eq!(rc.as_ptr(), ptr);
+
+    let cow = ArcCow::<str>::Owned("hello".to_owned());
+    assert_eq!(&*Rc::<str>::from(cow), "hello");
+    assert_eq!(&*Arc::<str>::from(RcCow::Borrowed("hello")), "hello");
+}
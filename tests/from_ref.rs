@@ -0,0 +1,54 @@
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+mod common;
+
+use crate::common::count_allocations;
+
+/// Asserts that the conversion from `$source` is `Borrowed(_)` pointing to the
+/// source data, and allocates nothing.
+macro_rules! assert_borrowed {
+    ($cow:ident<$borrowed:ty>, $source:expr) => {{
+        let source = $source;
+        let (cow, count) = count_allocations(|| $cow::<$borrowed>::from(&source));
+        assert_eq!(count, 0, "`{}` allocated", stringify!($source));
+        assert!(
+            cow.is_borrowed(),
+            "`{}` is not borrowed",
+            stringify!($source)
+        );
+        let borrowed: &$borrowed = &source;
+        assert!(std::ptr::eq::<$borrowed>(&*cow, borrowed));
+    }};
+}
+
+/// Asserts `assert_borrowed!` for the owned type, `Box`, `Rc` and `Arc`.
+macro_rules! assert_borrowed_all {
+    ($cow:ident<$borrowed:ty>, $owned:expr) => {{
+        assert_borrowed!($cow<$borrowed>, $owned);
+        assert_borrowed!($cow<$borrowed>, Box::<$borrowed>::from($owned));
+        assert_borrowed!($cow<$borrowed>, Rc::<$borrowed>::from($owned));
+        assert_borrowed!($cow<$borrowed>, Arc::<$borrowed>::from($owned));
+    }};
+}
+
+macro_rules! from_ref_tests {
+    ($name:ident, $cow:ident) => {
+        #[test]
+        fn $name() {
+            assert_borrowed_all!($cow<str>, String::from("hello"));
+            assert_borrowed_all!($cow<[u8]>, vec![1u8, 2, 3]);
+            assert_borrowed_all!($cow<[String]>, vec![String::from("hello")]);
+            assert_borrowed_all!($cow<Path>, PathBuf::from("/tmp"));
+            assert_borrowed_all!($cow<OsStr>, OsString::from("hello"));
+            assert_borrowed_all!($cow<CStr>, CString::new("hello").unwrap());
+        }
+    };
+}
+
+from_ref_tests! { arccow_from_ref, ArcCow }
+from_ref_tests! { rccow_from_ref, RcCow }
@@ -0,0 +1,95 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, ArcInterner, RcCow, RcInterner};
+
+mod common;
+
+use crate::common::count_allocations;
+
+#[test]
+fn intern_hits_share_the_data() {
+    let interner = ArcInterner::<str>::new();
+    let first = interner.intern(ArcCow::Owned("hello".to_owned()));
+    assert!(first.is_shared());
+    let second = interner.intern(ArcCow::Borrowed("hello"));
+    assert!(first.ptr_eq(&second));
+    let third = interner.intern(ArcCow::Shared(Arc::from("hello")));
+    assert!(first.ptr_eq(&third));
+    assert!(first.ptr_eq(&interner.get("hello").unwrap()));
+
+    let other = interner.intern(ArcCow::Borrowed("world"));
+    assert!(!first.ptr_eq(&other));
+    assert!(interner.get("missing").is_none());
+    assert_eq!(interner.len(), 2);
+
+    let interner = RcInterner::<str>::new();
+    let first = interner.intern(RcCow::Borrowed("hello"));
+    let second = interner.intern(RcCow::Shared(Rc::from("hello")));
+    assert!(first.ptr_eq(&second));
+    assert!(first.ptr_eq(&interner.get("hello").unwrap()));
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn borrowed_lookups_do_not_allocate() {
+    let interner = ArcInterner::<str>::new();
+    let interned = interner.intern(ArcCow::Borrowed("hello"));
+    let (found, count) = count_allocations(|| interner.intern(ArcCow::Borrowed("hello")));
+    assert_eq!(count, 0);
+    assert!(interned.ptr_eq(&found));
+    let (found, count) = count_allocations(|| interner.get("hello"));
+    assert_eq!(count, 0);
+    assert!(interned.ptr_eq(&found.unwrap()));
+
+    let interner = RcInterner::<str>::new();
+    let interned = interner.intern(RcCow::Borrowed("hello"));
+    let (found, count) = count_allocations(|| interner.intern(RcCow::Borrowed("hello")));
+    assert_eq!(count, 0);
+    assert!(interned.ptr_eq(&found));
+}
+
+#[test]
+fn strong_interner_keeps_values() {
+    let interner = ArcInterner::<str>::new();
+    let weak = Arc::downgrade(&interner.intern(ArcCow::Borrowed("hello")).into_shared());
+    assert!(weak.upgrade().is_some());
+    interner.purge();
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn weak_interner_frees_values() {
+    let interner = ArcInterner::<str>::with_shards_weak(1);
+    let shared = interner.intern(ArcCow::Borrowed("hello")).into_shared();
+    let weak = Arc::downgrade(&shared);
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert_eq!(weak.weak_count(), 2);
+    drop(shared);
+    assert!(weak.upgrade().is_none());
+    assert!(interner.get("hello").is_none());
+    assert!(interner.is_empty());
+
+    let shared = interner.intern(ArcCow::Borrowed("hello")).into_shared();
+    drop(shared);
+    assert_eq!(interner.len(), 1);
+    interner.purge();
+    assert!(interner.is_empty());
+}
+
+#[test]
+fn weak_interner_purges_on_insertion() {
+    let interner = ArcInterner::<str>::with_shards_weak(1);
+    let kept = interner.intern(ArcCow::Borrowed("kept"));
+    for i in 0..1000 {
+        let _ = interner.intern(ArcCow::Owned(i.to_string()));
+        assert!(interner.len() <= 32, "{} entries", interner.len());
+    }
+    assert!(kept.ptr_eq(&interner.get("kept").unwrap()));
+
+    let interner = RcInterner::<str>::new_weak();
+    for i in 0..1000 {
+        let _ = interner.intern(RcCow::Owned(i.to_string()));
+        assert!(interner.len() <= 32, "{} entries", interner.len());
+    }
+}
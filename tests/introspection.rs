@@ -0,0 +1,90 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, CowKind, RcCow};
+
+#[test]
+fn kind() {
+    let borrowed = ArcCow::Borrowed("a");
+    let owned = ArcCow::<str>::Owned("a".to_owned());
+    let shared = ArcCow::Shared(Arc::<str>::from("a"));
+
+    assert_eq!(borrowed.kind(), CowKind::Borrowed);
+    assert_eq!(owned.kind(), CowKind::Owned);
+    assert_eq!(shared.kind(), CowKind::Shared);
+
+    assert!(borrowed.is_borrowed() && !borrowed.is_owned() && !borrowed.is_shared());
+    assert!(!owned.is_borrowed() && owned.is_owned() && !owned.is_shared());
+    assert!(!shared.is_borrowed() && !shared.is_owned() && shared.is_shared());
+}
+
+#[test]
+fn as_shared_and_counts() {
+    let rc = Rc::<str>::from("a");
+    let cow = RcCow::Shared(rc.clone());
+    assert!(Rc::ptr_eq(cow.as_shared().unwrap(), &rc));
+    assert_eq!(cow.strong_count(), Some(2));
+    assert_eq!(cow.weak_count(), Some(0));
+    let weak = Rc::downgrade(&rc);
+    drop(rc);
+    assert_eq!(cow.strong_count(), Some(1));
+    assert_eq!(cow.weak_count(), Some(1));
+    drop(weak);
+
+    let cow = RcCow::Borrowed("a");
+    assert!(cow.as_shared().is_none());
+    assert_eq!(cow.strong_count(), None);
+    assert_eq!(cow.weak_count(), None);
+    let cow = RcCow::<str>::Owned("a".to_owned());
+    assert!(cow.as_shared().is_none());
+    assert_eq!(cow.strong_count(), None);
+}
+
+#[test]
+fn ptr_eq() {
+    let arc = Arc::<str>::from("hello");
+    let shared = ArcCow::Shared(arc.clone());
+    assert!(shared.ptr_eq(&ArcCow::Shared(arc.clone())));
+    assert!(shared.ptr_eq(&ArcCow::Borrowed(&*arc)));
+    assert!(ArcCow::Borrowed(&*arc).ptr_eq(&shared));
+    assert!(shared.ptr_eq(&RcCow::Borrowed(&*arc)));
+    assert!(!shared.ptr_eq(&ArcCow::Shared(Arc::<str>::from("hello"))));
+    assert!(!shared.ptr_eq(&ArcCow::Borrowed(&arc[1..])));
+
+    let owned = ArcCow::<str>::Owned("hello".to_owned());
+    assert!(owned.ptr_eq(&owned.reborrow()));
+    assert!(!owned.ptr_eq(&ArcCow::Owned("hello".to_owned())));
+}
+
+#[test]
+fn ptr_eq_empty_data() {
+    // Empty and zero-sized data may have the same dangling address.
+    let borrowed = ArcCow::<str>::Borrowed("");
+    assert!(!borrowed.ptr_eq(&ArcCow::Owned(String::new())));
+    assert!(!borrowed.ptr_eq(&borrowed));
+    let units = ArcCow::<[()]>::Owned(vec![(), ()]);
+    assert!(!units.ptr_eq(&ArcCow::Owned(vec![(), ()])));
+
+    // Shared data has its own allocation even if it is empty.
+    let arc = Arc::<str>::from("");
+    let shared = ArcCow::Shared(arc.clone());
+    assert!(shared.ptr_eq(&ArcCow::Shared(arc)));
+    assert!(!shared.ptr_eq(&ArcCow::Shared(Arc::<str>::from(""))));
+    let units = ArcCow::Shared(Arc::<[()]>::from(vec![(), ()]));
+    assert!(units.ptr_eq(&units.clone()));
+    assert!(!units.ptr_eq(&ArcCow::Shared(Arc::<[()]>::from(vec![(), ()]))));
+}
+
+#[test]
+fn debug() {
+    let borrowed = ArcCow::Borrowed("a");
+    let owned = ArcCow::<str>::Owned("a".to_owned());
+    let shared = ArcCow::Shared(Arc::<str>::from("a"));
+
+    for cow in [&borrowed, &owned, &shared] {
+        assert_eq!(format!("{:?}", cow), r#""a""#);
+    }
+    assert_eq!(format!("{:#?}", borrowed), "Borrowed(\n    \"a\",\n)");
+    assert_eq!(format!("{:#?}", owned), "Owned(\n    \"a\",\n)");
+    assert_eq!(format!("{:#?}", shared), "Shared(\n    \"a\",\n)");
+}
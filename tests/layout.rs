@@ -0,0 +1,21 @@
+use std::mem::size_of;
+
+use shared_cow::{ArcCow, RcCow};
+
+#[test]
+fn str_cow_is_three_words() {
+    assert_eq!(size_of::<ArcCow<'_, str>>(), 3 * size_of::<usize>());
+    assert_eq!(size_of::<RcCow<'_, str>>(), 3 * size_of::<usize>());
+}
+
+#[test]
+fn option_uses_niche() {
+    assert_eq!(
+        size_of::<Option<ArcCow<'_, str>>>(),
+        size_of::<ArcCow<'_, str>>()
+    );
+    assert_eq!(
+        size_of::<Option<RcCow<'_, str>>>(),
+        size_of::<RcCow<'_, str>>()
+    );
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, MaybeStatic, StaticArcCow, StaticRcCow};
+
+mod common;
+
+use crate::common::count_allocations;
+
+static KEYWORDS: [StaticArcCow<'static, str>; 3] = [
+    StaticArcCow::from_static("fn"),
+    StaticArcCow::from_static("let"),
+    StaticArcCow::from_static("match"),
+];
+
+#[test]
+fn static_table() {
+    assert!(KEYWORDS.iter().all(StaticArcCow::is_static));
+    assert_eq!(KEYWORDS[1], "let");
+
+    let (keyword, count) = count_allocations(|| KEYWORDS[2].clone());
+    assert_eq!(count, 0);
+    assert!(keyword.is_static());
+    assert!(keyword.ptr_eq(&KEYWORDS[2]));
+}
+
+#[test]
+fn conversions_keep_static_data() {
+    let cow = StaticRcCow::<str>::from_static("hello");
+    let ptr = cow.as_ptr();
+
+    let (cow, count) = count_allocations(|| cow.into_static());
+    assert_eq!(count, 0);
+    assert!(cow.is_static());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let (cow, count) = count_allocations(|| cow.to_static());
+    assert_eq!(count, 0);
+    assert!(cow.is_static());
+
+    let (shared, count) = count_allocations(|| cow.into_shared());
+    assert_eq!(count, 0);
+    assert!(matches!(shared, MaybeStatic::Static(_)));
+    assert_eq!(shared.as_ptr(), ptr);
+}
+
+#[test]
+fn to_mut_clones_static_data() {
+    let mut cow = StaticArcCow::<str>::from_static("hello");
+    let ptr = cow.as_ptr();
+    cow.to_mut().push('!');
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "hello!");
+    assert_ne!(cow.as_ptr(), ptr);
+
+    static BYTES: Vec<u8> = Vec::new();
+    let mut cow = StaticArcCow::<Vec<u8>>::from_static(&BYTES);
+    cow.to_mut().push(1);
+    assert_eq!(*cow, [1]);
+    assert!(BYTES.is_empty());
+}
+
+#[test]
+fn counts() {
+    let cow = StaticArcCow::<str>::from_static("hello");
+    assert_eq!(cow.strong_count(), None);
+    assert_eq!(cow.weak_count(), None);
+
+    let cow = ArcCow::Shared(Arc::<str>::from("hello")).into_maybe_static();
+    assert!(!cow.is_static());
+    assert_eq!(cow.strong_count(), Some(1));
+    assert_eq!(cow.weak_count(), Some(0));
+}
@@ -0,0 +1,59 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: String,
+    value: Vec<u8>,
+}
+
+fn entry() -> Entry {
+    Entry {
+        key: "key".to_owned(),
+        value: vec![1, 2, 3],
+    }
+}
+
+fn key(entry: &Entry) -> &str {
+    &entry.key
+}
+
+#[test]
+fn project_shared_keeps_parent_alive() {
+    let arc = Arc::new(entry());
+    let key_ptr = arc.key.as_ptr();
+    let cow = ArcCow::Shared(arc.clone());
+    let projected = cow.project(key);
+    assert!(projected.is_shared());
+    assert_eq!(Arc::strong_count(&arc), 3);
+    assert!(Arc::ptr_eq(projected.as_shared().unwrap().parent(), &arc));
+
+    drop(cow);
+    drop(arc);
+    let parent = projected.as_shared().unwrap().parent();
+    assert_eq!(Arc::strong_count(parent), 1);
+    assert_eq!(&*projected, "key");
+    assert_eq!(projected.as_ptr(), key_ptr);
+
+    let value = RcCow::Shared(Rc::new(entry())).project(|entry| &entry.value[1..]);
+    assert_eq!(*value, [2, 3]);
+    let parent = value.as_shared().unwrap().parent();
+    assert_eq!(value.as_ptr(), parent.value[1..].as_ptr());
+}
+
+#[test]
+fn project_borrowed_and_owned() {
+    let entry = entry();
+    let cow = ArcCow::Borrowed(&entry);
+    let projected = cow.project(key);
+    assert!(projected.is_borrowed());
+    assert_eq!(projected.as_ptr(), entry.key.as_ptr());
+
+    let cow = ArcCow::<Entry>::Owned(entry.clone());
+    let projected = cow.project(key);
+    assert!(projected.is_owned());
+    assert_eq!(&*projected, "key");
+    assert_ne!(projected.as_ptr(), cow.key.as_ptr());
+}
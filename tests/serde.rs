@@ -0,0 +1,266 @@
+#![cfg(feature = "serde")]
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, assert_ser_tokens, assert_tokens, Token};
+use shared_cow::serde::identity::{Preserved, PreservedSeed};
+use shared_cow::serde::InternSeed;
+use shared_cow::{ArcCow, ArcInterner, RcCow, RcInterner};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Static {
+    s: ArcCow<'static, str>,
+    bytes: ArcCow<'static, [u8]>,
+    path: RcCow<'static, Path>,
+    os_str: RcCow<'static, OsStr>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Borrowing<'a> {
+    #[serde(borrow, with = "shared_cow::serde::borrow")]
+    s: ArcCow<'a, str>,
+    #[serde(borrow, with = "shared_cow::serde::borrow")]
+    bytes: ArcCow<'a, [u8]>,
+    #[serde(borrow, with = "shared_cow::serde::borrow")]
+    path: ArcCow<'a, Path>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sharing<'a> {
+    #[serde(with = "shared_cow::serde::shared")]
+    shared: ArcCow<'a, str>,
+    #[serde(borrow, with = "shared_cow::serde::borrow_or_shared")]
+    borrow_or_shared: ArcCow<'a, str>,
+}
+
+fn static_value() -> Static {
+    Static {
+        s: ArcCow::Shared(Arc::from("hello")),
+        bytes: ArcCow::Borrowed(b"\x00\xff"),
+        path: RcCow::Borrowed(Path::new("/tmp/file")),
+        os_str: RcCow::Owned("os string".into()),
+    }
+}
+
+#[test]
+fn static_cows_are_deserialized_as_owned() {
+    let value = static_value();
+
+    let json = serde_json::to_string(&value).unwrap();
+    let de: Static = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, value);
+    assert!(de.s.is_owned());
+    assert!(de.bytes.is_owned());
+    assert!(de.path.is_owned());
+    assert!(de.os_str.is_owned());
+
+    let bin = bincode::serialize(&value).unwrap();
+    let de: Static = bincode::deserialize(&bin).unwrap();
+    assert_eq!(de, value);
+    assert!(de.s.is_owned());
+    assert!(de.bytes.is_owned());
+
+    let de: Static = bincode::deserialize_from(&bin[..]).unwrap();
+    assert_eq!(de, value);
+}
+
+#[test]
+fn borrowing_fields() {
+    let value = Borrowing {
+        s: ArcCow::Owned("hello".to_owned()),
+        bytes: ArcCow::Shared(Arc::from(&b"\x00\xff"[..])),
+        path: ArcCow::Borrowed(Path::new("/tmp/file")),
+    };
+
+    let bin = bincode::serialize(&value).unwrap();
+    let de: Borrowing<'_> = bincode::deserialize(&bin).unwrap();
+    assert_eq!(de, value);
+    assert!(de.s.is_borrowed());
+    assert!(de.bytes.is_borrowed());
+    assert!(de.path.is_borrowed());
+
+    let json = serde_json::to_string(&value).unwrap();
+    let de: Borrowing<'_> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, value);
+    assert!(de.s.is_borrowed());
+    // JSON has no byte strings.
+    assert!(de.bytes.is_owned());
+    assert!(de.path.is_borrowed());
+
+    // Escaped strings cannot be borrowed.
+    let json = r#"{"s":"a\"b","bytes":[],"path":"/tmp"}"#;
+    let de: Borrowing<'_> = serde_json::from_str(json).unwrap();
+    assert_eq!(&*de.s, "a\"b");
+    assert!(de.s.is_owned());
+}
+
+#[test]
+fn sharing_fields() {
+    let json = r#"{"shared":"hello","borrow_or_shared":"a\"b"}"#;
+    let de: Sharing<'_> = serde_json::from_str(json).unwrap();
+    assert_eq!(&*de.shared, "hello");
+    assert!(de.shared.is_shared());
+    assert_eq!(&*de.borrow_or_shared, "a\"b");
+    assert!(de.borrow_or_shared.is_shared());
+
+    let json = r#"{"shared":"hello","borrow_or_shared":"world"}"#;
+    let de: Sharing<'_> = serde_json::from_str(json).unwrap();
+    assert!(de.shared.is_shared());
+    assert!(de.borrow_or_shared.is_borrowed());
+    assert_eq!(serde_json::to_string(&de).unwrap(), json);
+}
+
+#[test]
+fn slices_are_serialized_as_sequences() {
+    for cow in [
+        ArcCow::Borrowed(&b"ab"[..]),
+        ArcCow::Owned(b"ab".to_vec()),
+        ArcCow::Shared(Arc::from(&b"ab"[..])),
+    ] {
+        assert_tokens(
+            &cow,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(b'a'),
+                Token::U8(b'b'),
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    let cow = RcCow::<[String]>::Owned(vec!["a".to_owned(), "b".to_owned()]);
+    let json = serde_json::to_string(&cow).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+    let de: RcCow<'static, [String]> = serde_json::from_str(&json).unwrap();
+    assert!(de.is_owned());
+    assert_eq!(de, cow);
+
+    let cow = ArcCow::Shared(Arc::<[u32]>::from(&[1, 2][..]));
+    let bin = bincode::serialize(&cow).unwrap();
+    let de: ArcCow<'static, [u32]> = bincode::deserialize(&bin).unwrap();
+    assert_eq!(de, cow);
+}
+
+#[test]
+fn bytes_module() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Bytes {
+        #[serde(with = "shared_cow::serde::bytes")]
+        bytes: ArcCow<'static, [u8]>,
+    }
+
+    let value = Bytes {
+        bytes: ArcCow::Shared(Arc::from(&b"ab"[..])),
+    };
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Struct {
+                name: "Bytes",
+                len: 1,
+            },
+            Token::Str("bytes"),
+            Token::Bytes(b"ab"),
+            Token::StructEnd,
+        ],
+    );
+
+    let owned = Bytes {
+        bytes: ArcCow::Owned(b"ab".to_vec()),
+    };
+    for bytes in [
+        Token::Bytes(b"ab"),
+        Token::BorrowedBytes(b"ab"),
+        Token::ByteBuf(b"ab"),
+    ] {
+        let tokens = [
+            Token::Struct {
+                name: "Bytes",
+                len: 1,
+            },
+            Token::Str("bytes"),
+            bytes,
+            Token::StructEnd,
+        ];
+        assert_de_tokens(&owned, &tokens);
+    }
+
+    let bin = bincode::serialize(&value).unwrap();
+    let de: Bytes = bincode::deserialize(&bin).unwrap();
+    assert!(de.bytes.is_owned());
+    assert_eq!(de, value);
+}
+
+#[test]
+fn sized_values() {
+    let cow = ArcCow::Shared(Arc::new(vec![1u32, 2]));
+    let json = serde_json::to_string(&cow).unwrap();
+    assert_eq!(json, "[1,2]");
+}
+
+#[test]
+fn identity_round_trip() {
+    #[derive(Serialize, Deserialize)]
+    struct Pair {
+        #[serde(with = "shared_cow::serde::identity")]
+        first: ArcCow<'static, str>,
+        #[serde(with = "shared_cow::serde::identity")]
+        second: ArcCow<'static, str>,
+    }
+
+    let shared: Arc<str> = Arc::from("hello");
+    let pair = Pair {
+        first: ArcCow::Shared(shared.clone()),
+        second: ArcCow::Shared(shared),
+    };
+    let json = serde_json::to_string(&Preserved::new(&pair)).unwrap();
+    let mut de = serde_json::Deserializer::from_str(&json);
+    let pair: Pair =
+        serde::de::DeserializeSeed::deserialize(PreservedSeed::new(), &mut de).unwrap();
+    assert_eq!(&*pair.first, "hello");
+    assert!(pair.first.ptr_eq(&pair.second));
+}
+
+#[test]
+fn intern_seed() {
+    use bincode::Options;
+    use serde::de::DeserializeSeed;
+
+    let interner = ArcInterner::<str>::new();
+    let mut de = serde_json::Deserializer::from_str(r#""hello""#);
+    let first = InternSeed::new(&interner).deserialize(&mut de).unwrap();
+    let mut de = serde_json::Deserializer::from_str(r#""hel\u006co""#);
+    let second = InternSeed::new(&interner).deserialize(&mut de).unwrap();
+    assert_eq!(&*second, "hello");
+    assert!(first.is_shared());
+    assert!(first.ptr_eq(&second));
+    assert_eq!(interner.len(), 1);
+
+    let interner = RcInterner::<[u8]>::new();
+    let options = bincode::options();
+    let bin = options.serialize(&ArcCow::Borrowed(&b"ab"[..])).unwrap();
+    let first = InternSeed::new(&interner)
+        .deserialize(&mut bincode::Deserializer::from_slice(&bin, options))
+        .unwrap();
+    let second = InternSeed::new(&interner)
+        .deserialize(&mut bincode::Deserializer::from_slice(&bin, options))
+        .unwrap();
+    assert_eq!(&*first, b"ab");
+    assert!(first.ptr_eq(&second));
+
+    let interner = RcInterner::<Path>::new();
+    let mut de = serde_json::Deserializer::from_str(r#""/tmp""#);
+    let path = InternSeed::new(&interner).deserialize(&mut de).unwrap();
+    assert_eq!(&*path, Path::new("/tmp"));
+    assert_eq!(interner.len(), 1);
+
+    let interner = ArcInterner::<OsStr>::new();
+    let json = serde_json::to_string(&ArcCow::Borrowed(OsStr::new("os"))).unwrap();
+    let mut de = serde_json::Deserializer::from_str(&json);
+    let os_str = InternSeed::new(&interner).deserialize(&mut de).unwrap();
+    assert_eq!(&*os_str, OsStr::new("os"));
+    assert_eq!(interner.len(), 1);
+}
@@ -0,0 +1,112 @@
+use std::ops::Bound;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, SlicedArcCow};
+
+fn shared(s: &str) -> SlicedArcCow<'static, str> {
+    ArcCow::Shared(Arc::<str>::from(s)).into_sliced()
+}
+
+#[test]
+fn nested_slice_offsets() {
+    let whole = shared("hello, world");
+    let world = whole.slice(7..);
+    assert_eq!(&*world, "world");
+    let or = world.slice(1..3);
+    assert_eq!(&*or, "or");
+    let r = or.slice(1..);
+    assert_eq!(&*r, "r");
+    let rl = world.slice(2..=3);
+    assert_eq!(&*rl, "rl");
+
+    assert!(r.is_shared());
+    assert_eq!(r.as_ptr(), whole[9..].as_ptr());
+    assert_eq!(whole.strong_count(), Some(5));
+}
+
+#[test]
+fn slice_with_bounds() {
+    let whole = shared("0123456789");
+    let sub = whole.slice((Bound::Excluded(1), Bound::Included(8)));
+    assert_eq!(&*sub, "2345678");
+    let sub = sub.slice((Bound::Excluded(0), Bound::Unbounded));
+    assert_eq!(&*sub, "345678");
+    let sub = sub.slice((Bound::Unbounded, Bound::Excluded(3)));
+    assert_eq!(&*sub, "345");
+    assert_eq!(&*sub.slice(..), "345");
+    assert_eq!(&*sub.slice(3..), "");
+}
+
+#[test]
+#[should_panic]
+fn slice_out_of_current_range() {
+    let whole = shared("hello, world");
+    let hello = whole.slice(..5);
+    // The whole data is longer, but this is out of `hello`.
+    let _ = hello.slice(..6);
+}
+
+#[test]
+#[should_panic]
+fn slice_not_at_char_boundary() {
+    let whole = shared("\u{3042}\u{3044}");
+    let _ = whole.slice(1..);
+}
+
+#[test]
+fn slice_borrowed_and_owned() {
+    let borrowed = SlicedArcCow::Borrowed("hello, world");
+    let sub = borrowed.slice(7..);
+    assert!(sub.is_borrowed());
+    assert_eq!(&*sub, "world");
+
+    let owned = SlicedArcCow::<str>::Owned("hello, world".to_owned());
+    let sub = owned.slice(..5);
+    assert!(sub.is_owned());
+    assert_eq!(&*sub, "hello");
+}
+
+#[test]
+fn split_shares_the_data() {
+    let whole = shared("a,bc,,def");
+    let sub = whole.slice(2..);
+    let parts: Vec<_> = sub.split(",").collect();
+    assert_eq!(parts, ["bc", "", "def"]);
+    assert!(parts.iter().all(|part| part.is_shared()));
+    assert_eq!(parts[0].as_ptr(), whole[2..].as_ptr());
+    assert_eq!(parts[2].as_ptr(), whole[6..].as_ptr());
+}
+
+#[test]
+fn lines_and_split_whitespace() {
+    let whole = shared("first line\r\n  second\tline\nthird");
+    let lines: Vec<_> = whole.lines().collect();
+    assert_eq!(lines, ["first line", "  second\tline", "third"]);
+    assert_eq!(lines[1].as_ptr(), whole[12..].as_ptr());
+
+    let words: Vec<_> = lines[1].split_whitespace().collect();
+    assert_eq!(words, ["second", "line"]);
+    assert_eq!(words[0].as_ptr(), whole[14..].as_ptr());
+    assert!(words.iter().all(|word| word.is_shared()));
+}
+
+#[test]
+fn chunks() {
+    let data: Arc<[u8]> = Arc::from(&[0u8, 1, 2, 3, 4, 5, 6, 7, 8][..]);
+    let whole = ArcCow::Shared(data).into_sliced();
+    let sub = whole.slice(1..);
+    let chunks: Vec<_> = sub.chunks(3).collect();
+    assert_eq!(chunks, [&[1u8, 2, 3][..], &[4, 5, 6], &[7, 8]]);
+    assert!(chunks.iter().all(|chunk| chunk.is_shared()));
+    assert_eq!(chunks[1].as_ptr(), whole[4..].as_ptr());
+
+    assert_eq!(sub.slice(..0).chunks(3).count(), 0);
+}
+
+#[test]
+#[should_panic]
+fn chunks_of_zero_size() {
+    let whole = shared("abc").into_owned().into_bytes();
+    let cow = ArcCow::Shared(Arc::<[u8]>::from(whole)).into_sliced();
+    let _ = cow.chunks(0).count();
+}
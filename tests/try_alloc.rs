@@ -0,0 +1,149 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use std::collections::TryReserveError;
+
+use shared_cow::{ArcCow, RcCow, TryToOwned};
+
+mod common;
+
+use crate::common::with_allocation_limit;
+
+/// Length of the data, which cannot be allocated under `LIMIT`.
+const LEN: usize = 4096;
+
+/// Maximum size of the allocations in the tests.
+const LIMIT: usize = 1024;
+
+fn data() -> String {
+    "a".repeat(LEN)
+}
+
+/// Sized data with fallible clone.
+#[derive(Debug, Clone, PartialEq)]
+struct Buffer(Vec<u8>);
+
+impl TryToOwned for Buffer {
+    fn try_to_owned(&self) -> Result<Buffer, TryReserveError> {
+        let mut buf = Vec::new();
+        buf.try_reserve_exact(self.0.len())?;
+        buf.extend_from_slice(&self.0);
+        Ok(Buffer(buf))
+    }
+}
+
+#[test]
+fn unique_shared_data_is_moved() {
+    let buffer = Buffer(vec![0; LEN]);
+    let ptr = buffer.0.as_ptr();
+
+    let shared = Arc::new(buffer);
+    let cow = ArcCow::Shared(shared.clone());
+    let (cow, _) = with_allocation_limit(LIMIT, || cow.try_into_owned()).unwrap_err();
+    assert!(cow.is_shared());
+    drop(shared);
+    let buffer = with_allocation_limit(0, || cow.try_into_owned()).unwrap();
+    assert_eq!(buffer.0.as_ptr(), ptr);
+
+    let shared = Rc::new(buffer);
+    let mut cow = RcCow::Shared(shared.clone());
+    with_allocation_limit(LIMIT, || cow.try_to_mut().map(|_| ())).unwrap_err();
+    assert!(cow.is_shared());
+    drop(shared);
+    with_allocation_limit(0, || cow.try_to_mut())
+        .unwrap()
+        .0
+        .clear();
+    assert!(cow.is_owned());
+    assert_eq!(cow.0.as_ptr(), ptr);
+}
+
+#[test]
+fn try_into_owned() {
+    let data = data();
+
+    let cow = ArcCow::Borrowed(&*data);
+    let (cow, _) = with_allocation_limit(LIMIT, || cow.try_into_owned()).unwrap_err();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let shared = Arc::<str>::from(&*data);
+    let cow = ArcCow::Shared(shared.clone());
+    let (cow, _) = with_allocation_limit(LIMIT, || cow.try_into_owned()).unwrap_err();
+    assert!(cow.is_shared());
+    assert_eq!(cow.as_ptr(), shared.as_ptr());
+
+    let owned = ArcCow::<str>::Owned(data.clone());
+    let ptr = owned.as_ptr();
+    let owned = with_allocation_limit(LIMIT, || owned.try_into_owned()).unwrap();
+    assert_eq!(owned.as_ptr(), ptr);
+
+    let owned = with_allocation_limit(2 * LEN, || RcCow::Borrowed(&*data).try_into_owned());
+    assert_eq!(owned.unwrap(), data);
+}
+
+#[test]
+fn try_to_mut() {
+    let data = data();
+
+    let mut cow = RcCow::Borrowed(&*data);
+    with_allocation_limit(LIMIT, || cow.try_to_mut()).unwrap_err();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let shared = Rc::<str>::from(&*data);
+    let mut cow = RcCow::Shared(shared.clone());
+    with_allocation_limit(LIMIT, || cow.try_to_mut()).unwrap_err();
+    assert!(cow.is_shared());
+    assert_eq!(cow.as_ptr(), shared.as_ptr());
+
+    with_allocation_limit(2 * LEN, || cow.try_to_mut())
+        .unwrap()
+        .push('b');
+    assert!(cow.is_owned());
+    assert_eq!(cow.len(), LEN + 1);
+}
+
+#[test]
+fn try_into_shared() {
+    let data = data();
+
+    let cow = ArcCow::Borrowed(&*data);
+    let (cow, _) = with_allocation_limit(LIMIT, || cow.try_into_shared()).unwrap_err();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let cow = ArcCow::<str>::Owned(data.clone());
+    let ptr = cow.as_ptr();
+    let (cow, _) = with_allocation_limit(LIMIT, || cow.try_into_shared()).unwrap_err();
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let shared = Arc::<str>::from(&*data);
+    let cow = ArcCow::Shared(shared.clone());
+    let arc = with_allocation_limit(LIMIT, || cow.try_into_shared()).unwrap();
+    assert!(Arc::ptr_eq(&arc, &shared));
+
+    let rc = with_allocation_limit(2 * LEN, || RcCow::Borrowed(&*data).try_into_shared());
+    assert_eq!(&*rc.unwrap(), data);
+}
+
+#[test]
+fn try_share() {
+    let data = data();
+
+    let mut cow = RcCow::<str>::Owned(data.clone());
+    let ptr = cow.as_ptr();
+    with_allocation_limit(LIMIT, || cow.try_share().map(|_| ())).unwrap_err();
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let mut cow = RcCow::Borrowed(&*data);
+    with_allocation_limit(LIMIT, || cow.try_share().map(|_| ())).unwrap();
+    assert!(cow.is_borrowed());
+
+    let mut cow = RcCow::<str>::Owned(data.clone());
+    with_allocation_limit(2 * LEN, || cow.try_share().map(|_| ())).unwrap();
+    assert!(cow.is_shared());
+    assert_eq!(&*cow, data);
+}
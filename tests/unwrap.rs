@@ -0,0 +1,110 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+mod common;
+
+use crate::common::count_allocations;
+
+fn data() -> Vec<u8> {
+    vec![1, 2, 3]
+}
+
+#[test]
+fn to_mut_allocations() {
+    let data = data();
+
+    let mut cow = ArcCow::<Vec<u8>>::Owned(data.clone());
+    let (_, count) = count_allocations(|| {
+        cow.to_mut();
+    });
+    cow.to_mut().push(4);
+    assert_eq!(count, 0);
+
+    let mut cow = ArcCow::Borrowed(&data);
+    let (_, count) = count_allocations(|| {
+        cow.to_mut();
+    });
+    cow.to_mut().push(4);
+    assert_eq!(count, 1);
+    assert_eq!(*cow, [1, 2, 3, 4]);
+
+    // The data is moved out of the unique pointer.
+    let mut cow = ArcCow::Shared(Arc::new(data.clone()));
+    let ptr = cow.as_ptr();
+    let (_, count) = count_allocations(|| {
+        cow.to_mut();
+    });
+    assert_eq!(count, 0);
+    assert!(cow.is_owned());
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let shared = Rc::new(data.clone());
+    let mut cow = RcCow::Shared(shared.clone());
+    let (_, count) = count_allocations(|| {
+        cow.to_mut();
+    });
+    cow.to_mut().push(4);
+    assert_eq!(count, 1);
+    assert_eq!(*cow, [1, 2, 3, 4]);
+    assert_eq!(*shared, [1, 2, 3]);
+}
+
+#[test]
+fn into_owned_allocations() {
+    let data = data();
+
+    let cow = ArcCow::<Vec<u8>>::Owned(data.clone());
+    let (_, count) = count_allocations(|| cow.into_owned());
+    assert_eq!(count, 0);
+
+    let cow = ArcCow::Borrowed(&data);
+    let (owned, count) = count_allocations(|| cow.into_owned());
+    assert_eq!(count, 1);
+    assert_eq!(owned, data);
+
+    let cow = RcCow::Shared(Rc::new(data.clone()));
+    let ptr = cow.as_ptr();
+    let (owned, count) = count_allocations(|| cow.into_owned());
+    assert_eq!(count, 0);
+    assert_eq!(owned.as_ptr(), ptr);
+
+    let shared = Arc::new(data.clone());
+    let cow = ArcCow::Shared(shared.clone());
+    let (owned, count) = count_allocations(|| cow.into_owned());
+    assert_eq!(count, 1);
+    assert_eq!(owned, *shared);
+}
+
+#[test]
+fn unsized_shared_is_cloned() {
+    let mut cow = ArcCow::Shared(Arc::<str>::from("hello"));
+    let (_, count) = count_allocations(|| {
+        cow.to_mut();
+    });
+    cow.to_mut().push('!');
+    assert_eq!(count, 1);
+    assert_eq!(&*cow, "hello!");
+}
+
+#[test]
+fn cross_family_conversion_allocations() {
+    let data = data();
+
+    // Only the new `Rc` is allocated, and the vector is moved into it.
+    let cow = ArcCow::Shared(Arc::new(data.clone()));
+    let ptr = cow.as_ptr();
+    let (cow, count) = count_allocations(|| cow.into_rccow());
+    assert_eq!(count, 1);
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let cow = cow.into_arccow();
+    assert_eq!(cow.as_ptr(), ptr);
+
+    let shared = Rc::new(data.clone());
+    let cow = RcCow::Shared(shared.clone());
+    let (cow, count) = count_allocations(|| cow.into_arccow());
+    assert_eq!(count, 2);
+    assert_eq!(*cow, *shared);
+}
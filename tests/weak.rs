@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+#[test]
+fn downgrade_and_upgrade() {
+    let cow = ArcCow::Shared(Arc::<str>::from("hello"));
+    let weak = cow.downgrade().unwrap();
+    assert_eq!(weak.strong_count(), 1);
+    assert_eq!(cow.weak_count(), Some(1));
+
+    let upgraded = weak.upgrade().unwrap();
+    assert!(upgraded.is_shared());
+    assert!(upgraded.ptr_eq(&cow));
+    assert_eq!(weak.strong_count(), 2);
+
+    drop(cow);
+    drop(upgraded);
+    assert_eq!(weak.strong_count(), 0);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn downgrade_unshared() {
+    assert!(RcCow::Borrowed("hello").downgrade().is_none());
+    assert!(RcCow::<str>::Owned("hello".to_owned())
+        .downgrade()
+        .is_none());
+}
+
+#[test]
+fn identity() {
+    let rc = Rc::<str>::from("hello");
+    let first = RcCow::Shared(rc.clone()).downgrade().unwrap();
+    let second = RcCow::Shared(rc.clone()).downgrade().unwrap();
+    let other = RcCow::Shared(Rc::<str>::from("hello")).downgrade().unwrap();
+
+    assert!(first.ptr_eq(&second));
+    assert!(first.ptr_eq(&first.clone()));
+    assert!(!first.ptr_eq(&other));
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+
+    let set: HashSet<_> = vec![first.clone(), second, other].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&first));
+}
@@ -0,0 +1,53 @@
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::sync::Arc;
+
+use shared_cow::{ArcCow, RcCow};
+
+#[test]
+fn fmt_write_empty_keeps_variant() {
+    let data = "abc";
+    let mut cow = ArcCow::Borrowed(data);
+    write!(cow, "").unwrap();
+    write!(cow, "{}", String::new()).unwrap();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let shared = Arc::<str>::from("abc");
+    let mut cow = ArcCow::Shared(shared.clone());
+    write!(cow, "").unwrap();
+    assert!(cow.is_shared());
+    assert!(Arc::ptr_eq(cow.as_shared().unwrap(), &shared));
+
+    write!(cow, "{}", 1).unwrap();
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "abc1");
+    assert_eq!(&*shared, "abc");
+
+    let mut cow = RcCow::Borrowed("abc");
+    cow.write_char('d').unwrap();
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, "abcd");
+}
+
+#[test]
+fn io_write_empty_keeps_variant() {
+    let data: &[u8] = b"abc";
+    let mut cow = ArcCow::Borrowed(data);
+    assert_eq!(cow.write(&[]).unwrap(), 0);
+    cow.write_all(&[]).unwrap();
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.as_ptr(), data.as_ptr());
+
+    let shared = Arc::<[u8]>::from(data);
+    let mut cow = ArcCow::Shared(shared.clone());
+    assert_eq!(cow.write(&[]).unwrap(), 0);
+    assert!(cow.is_shared());
+    assert!(Arc::ptr_eq(cow.as_shared().unwrap(), &shared));
+
+    assert_eq!(cow.write(b"de").unwrap(), 2);
+    cow.flush().unwrap();
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, b"abcde");
+    assert_eq!(&*shared, b"abc");
+}